@@ -16,6 +16,10 @@ interface BlocksEnv {
     blocks_out_create_event: (atom: number, type: number) => void
     blocks_out_delete_event: (atom: number, type: number) => void
     blocks_out_inject_stylesheet: (ptr: number, len: number) => void
+    blocks_out_now: () => number
+    blocks_out_timeout: (id: number, delay: number) => void
+    blocks_out_storage_get: (ptr: number, len: number, lengthPtr: number) => number
+    blocks_out_storage_set: (keyPtr: number, keyLen: number, ptr: number, len: number) => void
 }
 
 const load = async (binary: string, mount: string) => {
@@ -95,6 +99,21 @@ const load = async (binary: string, mount: string) => {
         }),
         blocks_out_inject_stylesheet: withWrapper(wrapper => (ptr: number, len: number) => {
             wrapper.injectStylesheet(wrapper.readString(ptr, len))
+        }),
+        blocks_out_now: (): number => {
+            return performance.now()
+        },
+        blocks_out_timeout: withWrapper(wrapper => (id: number, delay: number) => {
+            setTimeout(() => wrapper.callbackTimeout(id), delay)
+        }),
+        blocks_out_storage_get: withWrapper(wrapper => (ptr: number, len: number, lengthPtr: number): number => {
+            const value = localStorage.getItem(wrapper.readString(ptr, len))
+
+            // A null pointer signals a missing key.
+            return value === null ? 0 : wrapper.returnString(value, lengthPtr)
+        }),
+        blocks_out_storage_set: withWrapper(wrapper => (keyPtr: number, keyLen: number, ptr: number, len: number) => {
+            localStorage.setItem(wrapper.readString(keyPtr, keyLen), wrapper.readString(ptr, len))
         })
     }
 
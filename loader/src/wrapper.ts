@@ -4,6 +4,7 @@ import { EventMap, EventType } from './events'
 interface Callbacks {
     callback0: (f: number) => void
     callbackEvent: (atom: number, type: number, ptr: number, len: number) => void
+    callbackTimeout: (id: number) => void
     createString: (length: number) => number
 }
 
@@ -18,6 +19,7 @@ export class Wrapper {
         return {
             callback0: this.module.instance.exports.blocks_in_callback0,
             callbackEvent: this.module.instance.exports.blocks_in_callback_event,
+            callbackTimeout: this.module.instance.exports.blocks_in_callback_timeout,
             createString: this.module.instance.exports.blocks_in_create_string,
         }
     }
@@ -36,6 +38,10 @@ export class Wrapper {
         this.callbacks().callbackEvent(id, type, ptr, json.length)
     }
 
+    callbackTimeout(id: number) {
+        this.callbacks().callbackTimeout(id)
+    }
+
     createString(text: string): number {
         const mem = this.mem()
         const ptr = this.callbacks().createString(text.length)
@@ -110,10 +116,15 @@ export class Wrapper {
     }
 
     mountString(lengthPtr: number): number {
+        return this.returnString(this.mount, lengthPtr)
+    }
+
+    // Pass a string to rust, writing its little endian length to `lengthPtr`.
+    returnString(text: string, lengthPtr: number): number {
         const mem = this.mem()
 
-        const length = this.mount.length
-        const ptr = this.createString(this.mount)
+        const length = text.length
+        const ptr = this.createString(text)
 
         mem[lengthPtr] =     (length & 0x000000ff) >> 0
         mem[lengthPtr + 1] = (length & 0x0000ff00) >> 8
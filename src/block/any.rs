@@ -0,0 +1,119 @@
+use std::sync::Arc;
+
+use ::Update;
+use events::{Action, EventHandler};
+use block::{Block, BlockData, Build, Child, Walker};
+use block::bake::{BakedChild, BakedWalker, Forward};
+use block::scope::Scope;
+
+/// Bakes a child, given its scope and the update target of its position.
+type Bake<M> = Box<for<'a> FnOnce(Scope<'a>, Box<Update<Message = M>>) -> BakedChild<M>>;
+
+/// A child of any type.
+pub struct AnyChild<M>(Bake<M>);
+
+impl<M> AnyChild<M> where M: 'static + Send {
+    /// Erase the type of a child.
+    pub fn new<C>(child: C) -> Self where C: Child<M> {
+        AnyChild::from_bake(move |scope: Scope, update: Box<Update<Message = M>>| {
+            child.walk(BakedWalker::<M>::new(scope, update))
+        })
+    }
+
+    /// A child that bakes itself.
+    pub(crate) fn from_bake<W>(bake: W) -> Self
+    where
+        W: 'static + for<'a> FnOnce(Scope<'a>, Box<Update<Message = M>>) -> BakedChild<M>,
+    {
+        AnyChild(Box::new(bake))
+    }
+
+    pub(crate) fn bake(self, scope: Scope, update: Box<Update<Message = M>>) -> BakedChild<M> {
+        (self.0)(scope, update)
+    }
+
+    pub(crate) fn upgrade<N>(self) -> AnyChild<N> where N: 'static + Send + From<M> {
+        AnyChild::from_bake(move |scope: Scope, update: Box<Update<Message = N>>| {
+            let update = Forward::new(update, Arc::new(|action: Action<M>| action.map(N::from)));
+
+            self.bake(scope, Box::new(update)).map(N::from)
+        })
+    }
+}
+
+impl<M> Child<M> for AnyChild<M> where M: 'static + Send {
+    fn walk<T>(self, walker: T) -> T::Walked where T: Walker, T::Message: From<M> {
+        walker.erased(self)
+    }
+}
+
+/// A block of any type, created with `Block::boxed`.
+///
+/// Useful when branches of an `if` or `match`, or the items of a collection,
+/// are different blocks with the same message type:
+///
+/// ```ignore
+/// let content = if state.editing {
+///     editor(state).boxed()
+/// } else {
+///     preview(state).boxed()
+/// };
+/// ```
+pub struct AnyBlock<M> {
+    data: Build<Box<EventHandler<Message = M>>>,
+    child: AnyChild<M>,
+}
+
+impl<M> AnyBlock<M> where M: 'static + Send {
+    pub fn new<B>(block: B) -> Self where B: Block<Message = M> {
+        let BlockData { data, child } = block.extract();
+
+        AnyBlock {
+            data: Build::with(data.style, Box::new(data.event_handler)),
+            child: AnyChild::new(child),
+        }
+    }
+}
+
+impl<M> Block for AnyBlock<M> where M: 'static + Send {
+    type Message = M;
+    type EventHandler = Box<EventHandler<Message = M>>;
+    type Child = AnyChild<M>;
+
+    fn extract(self) -> BlockData<Self::EventHandler, Self::Child> {
+        let AnyBlock { data, child } = self;
+
+        BlockData { data, child }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use events::{Event, Events, Coordinates};
+    use harness;
+    use super::*;
+
+    fn view(editing: bool) -> AnyBlock<u32> {
+        if editing {
+            let events = Events::new().click(|_| 1);
+
+            Build::with(Default::default(), events).block(("Save", 1)).boxed()
+        } else {
+            Build::new().block("Edit").boxed()
+        }
+    }
+
+    #[test]
+    fn branches() {
+        assert_eq!("Edit", harness::render(view(false)).text());
+
+        let node: harness::Node<u32> =
+            harness::render(Build::new().block((view(true), view(false))));
+        assert_eq!("Save1Edit", node.text());
+
+        match node.get(&[0]).unwrap().event(Event::Click(Coordinates { x: 0, y: 0 })).pop() {
+            Some(::events::Action::Message(message)) => assert_eq!(1, message),
+            _ => panic!("expected a message"),
+        }
+    }
+}
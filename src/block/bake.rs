@@ -0,0 +1,461 @@
+//! Backend independent walking of block trees.
+
+use std::marker::PhantomData;
+use std::sync::{Arc, Mutex};
+
+use ::Update;
+use ui::Style;
+use events::{Action, Effect, Event, EventHandler, Kind, Upgrade};
+use block::{proxy, Walker, Group, Child, Build, Consolidator, Mount, AnyChild};
+use block::scope::{self, Scope};
+
+/// A fully walked child, with all messages upgraded to the root message type.
+pub enum BakedChild<M> {
+    Text(String),
+    Empty,
+    Group(Vec<BakedChild<M>>),
+    Element {
+        style: Style,
+        events: Shared<M>,
+        child: Box<BakedChild<M>>,
+    },
+    /// A child reused from an earlier render while `token` is unchanged.
+    Memo {
+        token: u64,
+        /// The number of nodes the child renders to.
+        nodes: usize,
+        child: Reuse<M>,
+    },
+}
+
+/// A memoized child, only baked out when a backend needs its nodes.
+///
+/// Backends that kept the nodes of the last render with the same token can
+/// skip the child without looking at it.
+pub struct Reuse<M>(Arc<Fn() -> BakedChild<M> + Send + Sync>);
+
+impl<M> Reuse<M> where M: 'static + Send {
+    fn new(baked: BakedChild<M>) -> Self {
+        Reuse(Arc::new(move || baked.clone()))
+    }
+
+    /// A copy of the child.
+    pub fn bake(&self) -> BakedChild<M> {
+        (self.0)()
+    }
+
+    fn lift<N, F>(&self, f: &Arc<F>) -> Reuse<N>
+    where
+        F: 'static + Send + Sync + Fn(Action<M>) -> Action<N>,
+        N: 'static + Send,
+    {
+        let reuse = self.clone();
+        let f = f.clone();
+
+        Reuse(Arc::new(move || reuse.bake().lift(&f)))
+    }
+}
+
+impl<M> Clone for Reuse<M> {
+    fn clone(&self) -> Self {
+        Reuse(self.0.clone())
+    }
+}
+
+impl<M> Clone for BakedChild<M> {
+    fn clone(&self) -> Self {
+        match *self {
+            BakedChild::Text(ref text) => BakedChild::Text(text.clone()),
+            BakedChild::Empty => BakedChild::Empty,
+            BakedChild::Group(ref children) => BakedChild::Group(children.clone()),
+            BakedChild::Element { ref style, ref events, ref child } => BakedChild::Element {
+                style: style.clone(),
+                events: events.clone(),
+                child: child.clone(),
+            },
+            BakedChild::Memo { token, nodes, ref child } => BakedChild::Memo {
+                token,
+                nodes,
+                child: child.clone(),
+            },
+        }
+    }
+}
+
+impl<M> BakedChild<M> where M: 'static + Send {
+    /// Transform the messages of every handler in the tree.
+    pub fn map<N, F>(self, f: F) -> BakedChild<N>
+    where
+        F: 'static + Send + Sync + Fn(M) -> N,
+        N: 'static + Send,
+    {
+        let f = Arc::new(f);
+
+        self.lift(&Arc::new(move |action: Action<M>| {
+            let f = f.clone();
+            action.map(move |message| f(message))
+        }))
+    }
+
+    /// Transform the actions of every handler in the tree.
+    pub(crate) fn lift<N, F>(self, f: &Arc<F>) -> BakedChild<N>
+    where
+        F: 'static + Send + Sync + Fn(Action<M>) -> Action<N>,
+        N: 'static + Send,
+    {
+        match self {
+            BakedChild::Text(text) => BakedChild::Text(text),
+            BakedChild::Empty => BakedChild::Empty,
+            BakedChild::Group(children) =>
+                BakedChild::Group(children.into_iter().map(|child| child.lift(f)).collect()),
+            BakedChild::Element { style, events, child } => BakedChild::Element {
+                style,
+                events: Shared::new(Lift {
+                    handler: events,
+                    f: f.clone(),
+                    _message: PhantomData,
+                }),
+                child: Box::new(child.lift(f)),
+            },
+            BakedChild::Memo { token, nodes, child } => BakedChild::Memo {
+                token,
+                nodes,
+                child: child.lift(f),
+            },
+        }
+    }
+
+    /// The number of nodes this child renders to, once groups are flattened.
+    fn nodes(&self) -> usize {
+        match *self {
+            BakedChild::Text(..) | BakedChild::Element { .. } => 1,
+            BakedChild::Empty => 0,
+            BakedChild::Group(ref children) => children.iter().map(BakedChild::nodes).sum(),
+            BakedChild::Memo { nodes, .. } => nodes,
+        }
+    }
+}
+
+/// A baked event handler, shared between renders of a memoized child.
+pub struct Shared<M>(Arc<Mutex<Box<EventHandler<Message = Action<M>>>>>);
+
+impl<M> Shared<M> {
+    pub fn new<E>(handler: E) -> Self where E: 'static + EventHandler<Message = Action<M>> {
+        Shared(Arc::new(Mutex::new(Box::new(handler))))
+    }
+}
+
+impl<M> Clone for Shared<M> {
+    fn clone(&self) -> Self {
+        Shared(self.0.clone())
+    }
+}
+
+impl<M> EventHandler for Shared<M> where M: 'static + Send {
+    type Message = Action<M>;
+
+    fn event(&self, event: Event) -> Vec<Self::Message> {
+        self.0.lock().unwrap().event(event)
+    }
+
+    fn handles(&self, kind: Kind) -> bool {
+        self.0.lock().unwrap().handles(kind)
+    }
+}
+
+/// Turns the messages of a handler into actions.
+pub struct Act<E>(E);
+
+impl<E> Act<E> {
+    pub fn new(handler: E) -> Self {
+        Act(handler)
+    }
+}
+
+impl<E> EventHandler for Act<E> where E: EventHandler {
+    type Message = Action<E::Message>;
+
+    fn event(&self, event: Event) -> Vec<Self::Message> {
+        self.0.event(event).into_iter().map(Action::Message).collect()
+    }
+
+    fn handles(&self, kind: Kind) -> bool {
+        self.0.handles(kind)
+    }
+}
+
+/// Transforms the actions of a baked handler.
+struct Lift<M, N, F> {
+    handler: Shared<M>,
+    f: Arc<F>,
+    _message: PhantomData<N>,
+}
+
+impl<M, N, F> EventHandler for Lift<M, N, F>
+where
+    F: 'static + Send + Sync + Fn(Action<M>) -> Action<N>,
+    M: 'static + Send,
+    N: 'static + Send,
+{
+    type Message = Action<N>;
+
+    fn event(&self, event: Event) -> Vec<Self::Message> {
+        self.handler.event(event).into_iter().map(|action| (self.f)(action)).collect()
+    }
+
+    fn handles(&self, kind: Kind) -> bool {
+        self.handler.handles(kind)
+    }
+}
+
+/// Sends the actions of a part of the tree to the update target of its
+/// parent, transformed the same way as its handlers.
+pub struct Forward<M, N, F> {
+    update: Box<Update<Message = N>>,
+    f: Arc<F>,
+    _message: PhantomData<M>,
+}
+
+impl<M, N, F> Forward<M, N, F> {
+    pub fn new(update: Box<Update<Message = N>>, f: Arc<F>) -> Self {
+        Forward {
+            update,
+            f,
+            _message: PhantomData,
+        }
+    }
+}
+
+impl<M, N, F> Update for Forward<M, N, F>
+where
+    F: 'static + Send + Sync + Fn(Action<M>) -> Action<N>,
+    M: 'static + Send,
+    N: 'static + Send,
+{
+    type Message = M;
+
+    fn reduce(&self, message: M) {
+        (self.f)(Action::Message(message)).dispatch(&self.update);
+    }
+
+    fn clone(&self) -> Box<Update<Message = M>> {
+        Box::new(Forward::new(Update::clone(&self.update), self.f.clone()))
+    }
+
+    fn effect(&self, effect: Effect<M>) {
+        (self.f)(Action::Effect(effect)).dispatch(&self.update);
+    }
+}
+
+/// A walker that bakes a child into a `BakedChild`.
+pub struct BakedWalker<'a, M> {
+    scope: Scope<'a>,
+    /// Where messages sent from outside of event handlers go, such as those
+    /// of an embedded app.
+    update: Box<Update<Message = M>>,
+}
+
+impl<'a, M> BakedWalker<'a, M> {
+    pub fn new(scope: Scope<'a>, update: Box<Update<Message = M>>) -> Self {
+        BakedWalker { scope, update }
+    }
+}
+
+impl<'a, M> Walker for BakedWalker<'a, M> where M: 'static + Send {
+    type Message = M;
+    type Walked = BakedChild<M>;
+
+    fn group<M_, G>(self, group: G) -> Self::Walked
+    where
+        G: Group<M_>,
+        Self::Message: From<M_>,
+        M_: 'static + Send,
+    {
+        struct ConsolidatorImpl<'a, M> {
+            scope: Scope<'a>,
+            update: Box<Update<Message = M>>,
+            children: Vec<BakedChild<M>>,
+        }
+
+        impl<'a, 'b, M> Consolidator for &'b mut ConsolidatorImpl<'a, M> where M: 'static + Send {
+            type Message = M;
+
+            fn child<M_, C>(&mut self, child: C)
+            where
+                C: Child<M_>,
+                Self::Message: From<M_>,
+                M_: 'static
+            {
+                let scope = self.scope.child(self.children.len());
+
+                let walker = BakedWalker::<M>::new(scope, Update::clone(&self.update));
+
+                self.children.push(child.walk(walker));
+            }
+        }
+
+        let mut consolidator = ConsolidatorImpl::<M> {
+            scope: self.scope,
+            update: self.update,
+            children: Vec::new(),
+        };
+
+        {
+            let consolidator = proxy::Consolidate::new(&mut consolidator);
+
+            group.consolidate(consolidator);
+        }
+
+        BakedChild::Group(consolidator.children)
+    }
+
+    fn block<E, M_, C>(self, data: Build<E>, child: C) -> Self::Walked
+    where
+        E: EventHandler<Message = M_>,
+        C: Child<M_>,
+        Self::Message: From<M_>,
+        E: 'static,
+        M_: 'static + Send,
+    {
+        BakedChild::Element {
+            style: data.style,
+            events: Shared::new(Act::new(Upgrade::<E, M>::new(data.event_handler))),
+            child: Box::new(child.walk(BakedWalker::<M>::new(self.scope.child(0), self.update))),
+        }
+    }
+
+    fn component<C>(self, mut component: C) -> Self::Walked
+    where
+        C: Mount,
+        Self::Message: From<C::Output>,
+    {
+        let scope = match component.key() {
+            Some(key) => self.scope.keyed(key),
+            None => self.scope,
+        };
+
+        let state = scope.state(|| component.init());
+
+        // Local messages are reduced against the component state when the
+        // runtime gets to them, and its output is passed on to the parent.
+        let lift = {
+            let state = state.clone();
+
+            Arc::new(move |action: Action<C::Message>| {
+                let state = state.clone();
+
+                Action::Effect(Box::new(move || {
+                    let message = match action {
+                        Action::Message(message) => Some(message),
+                        Action::Effect(effect) => effect(),
+                    };
+
+                    message
+                        .and_then(|message| C::reduce(&mut state.lock().unwrap(), message))
+                        .map(M::from)
+                }))
+            })
+        };
+
+        let baked = {
+            let update = Forward::new(self.update, lift.clone());
+            let walker = BakedWalker::<C::Message>::new(scope.child(0), Box::new(update));
+
+            component.view(&state.lock().unwrap(), walker)
+        };
+
+        baked.lift(&lift)
+    }
+
+    fn provide<T, M_, C>(self, value: T, child: C) -> Self::Walked
+    where
+        T: 'static + Send + Clone + PartialEq,
+        C: Child<M_>,
+        Self::Message: From<M_>,
+        M_: 'static + Send,
+    {
+        let scope = self.scope.provide(value);
+
+        child.walk(BakedWalker::<M>::new(scope, self.update))
+    }
+
+    fn consume<T, M_, C, F>(self, f: F) -> Self::Walked
+    where
+        T: 'static,
+        F: FnOnce(Option<&T>) -> C,
+        C: Child<M_>,
+        Self::Message: From<M_>,
+        M_: 'static + Send,
+    {
+        let child = f(self.scope.context::<T>());
+
+        child.walk(BakedWalker::<M>::new(self.scope, self.update))
+    }
+
+    fn memo<K, M_, C, F>(self, inputs: K, f: F) -> Self::Walked
+    where
+        K: 'static + Send + PartialEq,
+        F: FnOnce() -> C,
+        C: Child<M_>,
+        Self::Message: From<M_>,
+        M_: 'static + Send,
+    {
+        struct Cache<K, M> {
+            inputs: K,
+            versions: Vec<u64>,
+            token: u64,
+            nodes: usize,
+            child: Reuse<M>,
+        }
+
+        let versions = self.scope.versions();
+        let cache = self.scope.state::<Option<Cache<K, M>>, _>(|| None);
+        let mut cache = cache.lock().unwrap();
+
+        if let Some(ref cache) = *cache {
+            if cache.inputs == inputs && cache.versions == versions {
+                // Nothing below is walked, so keep its state alive explicitly.
+                self.scope.keep();
+
+                return BakedChild::Memo {
+                    token: cache.token,
+                    nodes: cache.nodes,
+                    child: cache.child.clone(),
+                };
+            }
+        }
+
+        let baked = f().walk(BakedWalker::<M>::new(self.scope.child(0), self.update));
+        let token = scope::unique();
+        let nodes = baked.nodes();
+        let child = Reuse::new(baked);
+
+        *cache = Some(Cache {
+            inputs,
+            versions,
+            token,
+            nodes,
+            child: child.clone(),
+        });
+
+        BakedChild::Memo { token, nodes, child }
+    }
+
+    fn erased<M_>(self, child: AnyChild<M_>) -> Self::Walked
+    where
+        Self::Message: From<M_>,
+        M_: 'static + Send,
+    {
+        let update = Forward::new(self.update, Arc::new(|action: Action<M_>| action.map(M::from)));
+
+        child.bake(self.scope, Box::new(update)).map(M::from)
+    }
+
+    fn text(self, text: &str) -> Self::Walked {
+        BakedChild::Text(text.to_string())
+    }
+
+    fn empty(self) -> Self::Walked {
+        BakedChild::Empty
+    }
+}
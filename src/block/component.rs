@@ -0,0 +1,194 @@
+//! Blocks with their own state.
+//!
+//! A component keeps state that the app state does not need to know about,
+//! such as whether a dropdown is open. The state is created when the
+//! component is first rendered, kept for as long as it stays at the same
+//! position in the tree, and updated by the component's own messages:
+//!
+//! ```ignore
+//! struct Toggle(bool);
+//!
+//! impl Component for Toggle {
+//!     type Message = ();
+//!     type Output = Message;
+//!
+//!     fn reduce(&mut self, _: ()) -> Option<Message> {
+//!         self.0 = !self.0;
+//!         Some(Message::Toggled(self.0))
+//!     }
+//! }
+//!
+//! let toggle = component::stateful(|| Toggle(false), |toggle: &Toggle| {
+//!     Build::with(Default::default(), Events::new().click(|_| ()))
+//!         .block(if toggle.0 { "On" } else { "Off" })
+//! });
+//! ```
+//!
+//! Components rendered from a list should be given a key with
+//! `Stateful::key`.
+
+use std::hash::Hash;
+use std::marker::PhantomData;
+
+use block::{proxy, Block, Child, Mount, Walker};
+use block::scope;
+
+/// The state of a component.
+pub trait Component: Send + 'static {
+    /// Messages sent from the component's view.
+    type Message: 'static + Send;
+    /// Messages passed on to the parent.
+    type Output: 'static + Send;
+
+    /// Handle a message, optionally emitting a message to the parent.
+    fn reduce(&mut self, message: Self::Message) -> Option<Self::Output>;
+}
+
+/// A child rendered from component state.
+pub struct Stateful<C, I, V> {
+    init: Option<I>,
+    view: V,
+    key: Option<u64>,
+    _component: PhantomData<C>,
+}
+
+/// Create a stateful child from a state initializer and a view function.
+pub fn stateful<C, I, V, B>(init: I, view: V) -> Stateful<C, I, V>
+where
+    C: Component,
+    I: 'static + FnOnce() -> C,
+    V: 'static + Fn(&C) -> B,
+    B: Block<Message = C::Message>,
+{
+    Stateful {
+        init: Some(init),
+        view,
+        key: None,
+        _component: PhantomData,
+    }
+}
+
+impl<C, I, V> Stateful<C, I, V> {
+    /// Identify the component by a key instead of its position among its
+    /// siblings.
+    pub fn key<K>(mut self, key: K) -> Self where K: Hash {
+        self.key = Some(scope::key(key));
+        self
+    }
+}
+
+impl<C, I, V, B> Mount for Stateful<C, I, V>
+where
+    C: Component,
+    I: 'static + FnOnce() -> C,
+    V: 'static + Fn(&C) -> B,
+    B: Block<Message = C::Message>,
+{
+    type State = C;
+    type Message = C::Message;
+    type Output = C::Output;
+
+    fn key(&self) -> Option<u64> {
+        self.key
+    }
+
+    fn init(&mut self) -> C {
+        let init = self.init.take().expect("blocks: component initialized twice");
+
+        init()
+    }
+
+    fn view<T>(&self, state: &C, walker: T) -> T::Walked
+    where
+        T: Walker,
+        T::Message: From<C::Message>,
+    {
+        Child::<C::Message>::walk((self.view)(state), walker)
+    }
+
+    fn reduce(state: &mut C, message: C::Message) -> Option<C::Output> {
+        state.reduce(message)
+    }
+}
+
+impl<C, I, V, B, M> Child<M> for Stateful<C, I, V>
+where
+    C: Component,
+    I: 'static + FnOnce() -> C,
+    V: 'static + Fn(&C) -> B,
+    B: Block<Message = C::Message>,
+    M: 'static + Send + From<C::Output>,
+{
+    fn walk<T>(self, walker: T) -> T::Walked where T: Walker, T::Message: From<M> {
+        proxy::Walk::<T, M>::new(walker).component(self)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use events::{Event, Events, Coordinates};
+    use harness::Harness;
+    use block::Build;
+    use ::{State, Reactor};
+    use super::*;
+
+    /// Counts clicks, and tells the parent about every third one.
+    struct Clicks(u32);
+
+    impl Component for Clicks {
+        type Message = ();
+        type Output = u32;
+
+        fn reduce(&mut self, _: ()) -> Option<u32> {
+            self.0 += 1;
+
+            if self.0 % 3 == 0 {
+                Some(self.0)
+            } else {
+                None
+            }
+        }
+    }
+
+    struct App(Vec<u32>);
+
+    impl State for App {
+        type Message = u32;
+
+        fn new(_: Reactor<u32>) -> Self {
+            App(Vec::new())
+        }
+
+        fn reduce(&mut self, clicks: u32) {
+            self.0.push(clicks);
+        }
+    }
+
+    fn clicks() -> impl Child<u32> {
+        stateful(|| Clicks(0), |clicks: &Clicks| {
+            Build::with(Default::default(), Events::new().click(|_| ())).block(clicks.0)
+        })
+    }
+
+    fn app(_: &App) -> impl Block<Message = u32> {
+        Build::new().block((clicks(), clicks()))
+    }
+
+    fn click() -> Event {
+        Event::Click(Coordinates { x: 0, y: 0 })
+    }
+
+    #[test]
+    fn local_state() {
+        let mut harness = Harness::new(app);
+
+        for _ in 0..4 {
+            harness.event(&[0], click());
+        }
+
+        harness.event(&[1], click());
+
+        assert_eq!("41", harness.render().text());
+        assert_eq!(vec![3], harness.state().0);
+    }
+}
@@ -0,0 +1,108 @@
+//! Values passed down the block tree without threading them through every
+//! view function.
+//!
+//! A child wrapped in `provide` makes a value available to all of its
+//! descendants, and `consume` reads the closest provided value of a type while
+//! the tree is walked:
+//!
+//! ```ignore
+//! struct Theme { accent: Color }
+//!
+//! fn app(state: &State) -> impl Block<Message = Message> {
+//!     Build::new().block(context::provide(state.theme(), sidebar()))
+//! }
+//!
+//! fn button() -> impl Child<Message> {
+//!     context::consume(|theme: Option<&Theme>| { ... })
+//! }
+//! ```
+//!
+//! Consumers always see the current value. Memoized subtrees below a provider
+//! are rebuilt whenever the provided value changes.
+
+use std::marker::PhantomData;
+
+use block::{Child, Walker};
+
+/// A child with a context value for its subtree.
+pub struct Provide<T, C> {
+    value: T,
+    child: C,
+}
+
+/// Provide a value to a child and all of its descendants.
+///
+/// The value shadows any value of the same type provided further up.
+pub fn provide<T, C>(value: T, child: C) -> Provide<T, C>
+where
+    T: 'static + Send + Clone + PartialEq,
+{
+    Provide { value, child }
+}
+
+impl<T, C, M> Child<M> for Provide<T, C>
+where
+    T: 'static + Send + Clone + PartialEq,
+    C: Child<M>,
+    M: 'static + Send,
+{
+    fn walk<W>(self, walker: W) -> W::Walked where W: Walker, W::Message: From<M> {
+        walker.provide(self.value, self.child)
+    }
+}
+
+/// A child built from a context value.
+pub struct Consume<T, F> {
+    f: F,
+    _value: PhantomData<fn(&T)>,
+}
+
+/// Build a child from the closest provided value of type `T`, or `None` if no
+/// ancestor provides one.
+pub fn consume<T, F, C>(f: F) -> Consume<T, F> where T: 'static, F: FnOnce(Option<&T>) -> C {
+    Consume { f, _value: PhantomData }
+}
+
+impl<T, F, C, M> Child<M> for Consume<T, F>
+where
+    T: 'static,
+    F: 'static + FnOnce(Option<&T>) -> C,
+    C: Child<M>,
+    M: 'static + Send,
+{
+    fn walk<W>(self, walker: W) -> W::Walked where W: Walker, W::Message: From<M> {
+        walker.consume::<T, M, C, F>(self.f)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use harness;
+    use block::{Block, Build};
+    use super::*;
+
+    #[derive(Clone, PartialEq)]
+    struct Locale(&'static str);
+
+    fn greeting() -> impl Child<()> {
+        consume(|locale: Option<&Locale>| match locale.map(|l| l.0) {
+            Some("fr") => "Bonjour",
+            _ => "Hello",
+        })
+    }
+
+    fn app() -> impl Block<Message = ()> {
+        Build::new().block((
+            greeting(),
+            provide(Locale("fr"), Build::new().block((
+                greeting(),
+                provide(Locale("en"), greeting()),
+            ))),
+        ))
+    }
+
+    #[test]
+    fn nearest() {
+        assert_eq!("HelloBonjourHello", harness::render(app()).text());
+    }
+}
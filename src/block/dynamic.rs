@@ -0,0 +1,175 @@
+//! Children whose number or type is only known at runtime.
+//!
+//! Every child here is walked as a group, so each item gets its own position
+//! in the tree, and state kept for an item stays with its index unless the
+//! item is keyed.
+
+use block::{Child, Consolidator, Group, Walker};
+
+macro_rules! impl_child_group {
+    ($($T:ty),*) => {$(
+        impl<M, C> Child<M> for $T where C: Child<M>, M: 'static + Send {
+            fn walk<T>(self, walker: T) -> T::Walked where T: Walker, T::Message: From<M> {
+                walker.group(self)
+            }
+        }
+    )*}
+}
+
+impl_child_group!(Vec<C>, Option<C>, Box<[C]>);
+
+impl<M, C> Group<M> for Vec<C> where C: Child<M>, M: 'static + Send {
+    fn consolidate<T>(self, mut consolidator: T) where T: Consolidator, T::Message: From<M> {
+        for child in self {
+            consolidator.child(child);
+        }
+    }
+}
+
+impl<M, C> Group<M> for Box<[C]> where C: Child<M>, M: 'static + Send {
+    fn consolidate<T>(self, consolidator: T) where T: Consolidator, T::Message: From<M> {
+        self.into_vec().consolidate(consolidator);
+    }
+}
+
+impl<M, C> Group<M> for Option<C> where C: Child<M>, M: 'static + Send {
+    fn consolidate<T>(self, mut consolidator: T) where T: Consolidator, T::Message: From<M> {
+        if let Some(child) = self {
+            consolidator.child(child);
+        }
+    }
+}
+
+/// A child built from an iterator of children.
+pub struct Iter<I>(I);
+
+/// Render every item of an iterator as a child.
+///
+/// ```ignore
+/// Build::new().block(iter(state.todos.iter().map(todo)))
+/// ```
+pub fn iter<I>(iter: I) -> Iter<I::IntoIter> where I: IntoIterator, I::IntoIter: 'static {
+    Iter(iter.into_iter())
+}
+
+impl<M, I> Child<M> for Iter<I>
+where
+    I: 'static + Iterator,
+    I::Item: Child<M>,
+    M: 'static + Send,
+{
+    fn walk<T>(self, walker: T) -> T::Walked where T: Walker, T::Message: From<M> {
+        walker.group(self)
+    }
+}
+
+impl<M, I> Group<M> for Iter<I> where I: Iterator, I::Item: Child<M>, M: 'static + Send {
+    fn consolidate<T>(self, mut consolidator: T) where T: Consolidator, T::Message: From<M> {
+        for child in self.0 {
+            consolidator.child(child);
+        }
+    }
+}
+
+/// One of two children, for conditionals whose branches have different types.
+///
+/// The branches are at different positions, so switching branches does not
+/// hand the state kept by one branch to the other.
+pub enum Either<L, R> {
+    Left(L),
+    Right(R),
+}
+
+impl<M, L, R> Child<M> for Either<L, R> where L: Child<M>, R: Child<M>, M: 'static + Send {
+    fn walk<T>(self, walker: T) -> T::Walked where T: Walker, T::Message: From<M> {
+        walker.group(self)
+    }
+}
+
+impl<M, L, R> Group<M> for Either<L, R> where L: Child<M>, R: Child<M>, M: 'static + Send {
+    fn consolidate<T>(self, mut consolidator: T) where T: Consolidator, T::Message: From<M> {
+        match self {
+            Either::Left(child) => consolidator.child(child),
+            Either::Right(child) => {
+                // Nothing is rendered at the position of the left branch.
+                consolidator.child::<M, _>(());
+                consolidator.child(child);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use ::{State, Reactor};
+    use events::{Event, Events, Coordinates};
+    use harness::{self, Harness};
+    use block::{Block, Build};
+    use block::component::{stateful, Component};
+    use super::*;
+
+    fn view(items: Vec<u32>, selected: Option<u32>) -> impl Block<Message = ()> {
+        let selection = match selected {
+            Some(index) => Either::Left(("Selected ", index)),
+            None => Either::Right("Nothing selected"),
+        };
+
+        Build::new().block((
+            iter(items.clone().into_iter().map(|item| item * 10)),
+            items.into_boxed_slice(),
+            selected.map(|_| "!"),
+            selection,
+        ))
+    }
+
+    #[test]
+    fn render() {
+        assert_eq!("102012Nothing selected", harness::render(view(vec![1, 2], None)).text());
+        assert_eq!("!Selected 0", harness::render(view(vec![], Some(0))).text());
+    }
+
+    struct Clicks(u32);
+
+    impl Component for Clicks {
+        type Message = ();
+        type Output = bool;
+
+        fn reduce(&mut self, _: ()) -> Option<bool> {
+            self.0 += 1;
+            None
+        }
+    }
+
+    struct Side(bool);
+
+    impl State for Side {
+        type Message = bool;
+
+        fn new(_: Reactor<bool>) -> Self {
+            Side(true)
+        }
+
+        fn reduce(&mut self, left: bool) {
+            self.0 = left;
+        }
+    }
+
+    fn branches(side: &Side) -> impl Block<Message = bool> {
+        let clicks = || stateful(|| Clicks(0), |clicks: &Clicks| {
+            Build::with(Default::default(), Events::new().click(|_| ())).block(clicks.0)
+        });
+
+        Build::new().block(if side.0 { Either::Left(clicks()) } else { Either::Right(clicks()) })
+    }
+
+    #[test]
+    fn switch() {
+        let mut harness = Harness::new(branches);
+
+        harness.event(&[0], Event::Click(Coordinates { x: 0, y: 0 }));
+        assert_eq!("1", harness.render().text());
+
+        harness.send(false);
+        assert_eq!("0", harness.render().text());
+    }
+}
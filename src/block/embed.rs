@@ -0,0 +1,270 @@
+//! Complete apps rendered inside other apps.
+//!
+//! Any state and view function that could be passed to `web::launch` can be
+//! rendered as a child of another app instead, so that apps developed on
+//! their own can be composed. The embedded app keeps its own state, created
+//! with `State::new` when it is first rendered, and reduces its own messages,
+//! including those sent through its reactor. The parent chooses which of
+//! those messages it wants to hear about:
+//!
+//! ```ignore
+//! let picker = embed::app(picker::view, |message: &picker::Message| match *message {
+//!     picker::Message::Pick(date) => Some(Message::Due(date)),
+//!     _ => None,
+//! });
+//!
+//! Build::new().block(picker.props(embed::Input(state.due)))
+//! ```
+//!
+//! Messages of the embedded app, including those made from its props, are
+//! processed by the runtime of the parent, in order with the messages of the
+//! parent. Rendering never reduces them directly.
+
+use std::hash::Hash;
+use std::marker::PhantomData;
+use std::sync::Arc;
+
+use ::{State, Reactor, Update};
+use events::Action;
+use block::{AnyChild, Block, Child, Walker};
+use block::bake::{BakedWalker, Forward};
+use block::scope::{self, Scope};
+
+/// Input from the parent app.
+pub trait Props<M>: 'static + Send {
+    /// The message to reduce when these props replace the props of the last
+    /// render, if any.
+    fn message(&self, previous: Option<&Self>) -> Option<M>;
+}
+
+impl<M> Props<M> for () {
+    fn message(&self, _: Option<&()>) -> Option<M> {
+        None
+    }
+}
+
+/// Props that are reduced as a message whenever they change.
+pub struct Input<P>(pub P);
+
+impl<P, M> Props<M> for Input<P>
+where
+    P: 'static + Send + Clone + PartialEq,
+    M: From<P>,
+{
+    fn message(&self, previous: Option<&Self>) -> Option<M> {
+        match previous {
+            Some(previous) if previous.0 == self.0 => None,
+            _ => Some(M::from(self.0.clone())),
+        }
+    }
+}
+
+/// An app rendered as a child.
+pub struct App<S, V, O, P> {
+    view: V,
+    output: Arc<O>,
+    props: P,
+    key: Option<u64>,
+    _state: PhantomData<S>,
+}
+
+/// Embed an app, passing the messages picked out by `output` on to the
+/// parent.
+pub fn app<S, V, B, O, M>(view: V, output: O) -> App<S, V, O, ()>
+where
+    S: State<Message = B::Message>,
+    V: 'static + Fn(&S) -> B,
+    B: Block,
+    O: 'static + Send + Sync + Fn(&S::Message) -> Option<M>,
+{
+    App {
+        view,
+        output: Arc::new(output),
+        props: (),
+        key: None,
+        _state: PhantomData,
+    }
+}
+
+impl<S, V, O, P> App<S, V, O, P> {
+    /// Pass props to the app.
+    ///
+    /// Whenever the props turn into a message, it is queued ahead of anything
+    /// the app sends from `State::new`, so the app is only rendered before
+    /// its props are applied on its very first render.
+    pub fn props<Q>(self, props: Q) -> App<S, V, O, Q> where S: State, Q: Props<S::Message> {
+        App {
+            view: self.view,
+            output: self.output,
+            props,
+            key: self.key,
+            _state: PhantomData,
+        }
+    }
+
+    /// Keep the state of the app by a key rather than by its position, for
+    /// apps rendered from a list.
+    pub fn key<K>(mut self, key: K) -> Self where K: Hash {
+        self.key = Some(scope::key(key));
+        self
+    }
+}
+
+impl<S, V, B, O, P, M> Child<M> for App<S, V, O, P>
+where
+    S: State<Message = B::Message>,
+    V: 'static + Fn(&S) -> B,
+    B: Block,
+    B::EventHandler: 'static,
+    O: 'static + Send + Sync + Fn(&S::Message) -> Option<M>,
+    P: Props<S::Message>,
+    M: 'static + Send,
+{
+    fn walk<T>(self, walker: T) -> T::Walked where T: Walker, T::Message: From<M> {
+        let App { view, output, props, key, .. } = self;
+
+        walker.erased(AnyChild::from_bake(move |scope: Scope, update: Box<Update<Message = M>>| {
+            let scope = match key {
+                Some(key) => scope.keyed(key),
+                None => scope,
+            };
+
+            // The state is created below, once its reactor can be.
+            let state = scope.state::<Option<S>, _>(|| None);
+
+            // Messages of the app are reduced against its state when the
+            // runtime gets to them, and those picked by `output` are passed
+            // on to the parent.
+            let lift = {
+                let state = state.clone();
+                let output = output.clone();
+
+                Arc::new(move |action: Action<S::Message>| {
+                    let state = state.clone();
+                    let output = output.clone();
+
+                    Action::Effect(Box::new(move || {
+                        let message = match action {
+                            Action::Message(message) => Some(message),
+                            Action::Effect(effect) => effect(),
+                        };
+
+                        message.and_then(|message| {
+                            let parent = output(&message);
+
+                            if let Some(ref mut state) = *state.lock().unwrap() {
+                                state.reduce(message);
+                            }
+
+                            parent
+                        })
+                    }))
+                })
+            };
+
+            let forward = || Forward::new(Update::clone(&update), lift.clone());
+
+            {
+                let previous = scope.state::<Option<P>, _>(|| None);
+                let mut previous = previous.lock().unwrap();
+
+                if let Some(message) = props.message(previous.as_ref()) {
+                    forward().reduce(message);
+                }
+
+                *previous = Some(props);
+            }
+
+            if state.lock().unwrap().is_none() {
+                let initial = S::new(Reactor::new(forward()));
+                *state.lock().unwrap() = Some(initial);
+            }
+
+            let guard = state.lock().unwrap();
+            let state = guard.as_ref().unwrap();
+
+            let walker = BakedWalker::<S::Message>::new(scope.child(0), Box::new(forward()));
+
+            Child::<S::Message>::walk(view(state), walker).lift(&lift)
+        }))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use events::{Event, Events, Coordinates};
+    use harness::Harness;
+    use block::Build;
+    use super::*;
+
+    /// An app that knows nothing about the apps it is embedded in.
+    struct Counter {
+        count: u32,
+        step: u32,
+    }
+
+    enum Message {
+        Step(u32),
+        Add,
+    }
+
+    impl From<u32> for Message {
+        fn from(step: u32) -> Self {
+            Message::Step(step)
+        }
+    }
+
+    impl State for Counter {
+        type Message = Message;
+
+        fn new(reactor: Reactor<Message>) -> Self {
+            reactor.send(Message::Add);
+            Counter { count: 0, step: 1 }
+        }
+
+        fn reduce(&mut self, message: Message) {
+            match message {
+                Message::Step(step) => self.step = step,
+                Message::Add => self.count += self.step,
+            }
+        }
+    }
+
+    fn counter(state: &Counter) -> impl Block<Message = Message> {
+        Build::with(Default::default(), Events::new().click(|_| Message::Add)).block(state.count)
+    }
+
+    /// Counts the additions of the embedded counter.
+    struct Parent(u32);
+
+    impl State for Parent {
+        type Message = ();
+
+        fn new(_: Reactor<()>) -> Self {
+            Parent(0)
+        }
+
+        fn reduce(&mut self, _: ()) {
+            self.0 += 1;
+        }
+    }
+
+    fn parent(_: &Parent) -> impl Block<Message = ()> {
+        let counter = app(counter, |message: &Message| match *message {
+            Message::Add => Some(()),
+            Message::Step(..) => None,
+        });
+
+        Build::new().block(counter.props(Input(2)))
+    }
+
+    #[test]
+    fn embedded() {
+        let mut harness = Harness::new(parent);
+        harness.event(&[0], Event::Click(Coordinates { x: 0, y: 0 }));
+
+        // One addition from `State::new` and one from the click.
+        assert_eq!("4", harness.render().text());
+        assert_eq!(2, harness.state().0);
+    }
+}
@@ -0,0 +1,98 @@
+use std::marker::PhantomData;
+use std::sync::Arc;
+
+use ::Update;
+use events::Action;
+use block::{AnyChild, Child, Walker};
+use block::bake::{BakedWalker, Forward};
+use block::scope::Scope;
+
+/// A child with its messages transformed by a closure, created with
+/// `Child::map` or `Block::map`.
+pub struct Map<C, F, M> {
+    child: C,
+    f: Arc<F>,
+    _message: PhantomData<M>,
+}
+
+impl<C, F, M> Map<C, F, M> {
+    pub(crate) fn new(child: C, f: Arc<F>) -> Self {
+        Map {
+            child,
+            f,
+            _message: PhantomData,
+        }
+    }
+}
+
+impl<C, F, M, N> Child<N> for Map<C, F, M>
+where
+    C: Child<M>,
+    F: 'static + Send + Sync + Fn(M) -> N,
+    M: 'static + Send,
+    N: 'static + Send,
+{
+    fn walk<T>(self, walker: T) -> T::Walked where T: Walker, T::Message: From<N> {
+        let Map { child, f, .. } = self;
+
+        walker.erased(AnyChild::from_bake(move |scope: Scope, update: Box<Update<Message = N>>| {
+            let forward = {
+                let f = f.clone();
+                Arc::new(move |action: Action<M>| {
+                    let f = f.clone();
+                    action.map(move |message| f(message))
+                })
+            };
+
+            child
+                .walk(BakedWalker::<M>::new(scope, Box::new(Forward::new(update, forward))))
+                .map(move |message| f(message))
+        }))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use events::{Action, Event, Events, Coordinates};
+    use harness;
+    use block::{iter, Block, Build};
+    use super::*;
+
+    /// A list that knows nothing about the messages of its parent.
+    fn list(items: Vec<&'static str>) -> impl Child<(usize, &'static str)> {
+        iter(items.into_iter().enumerate().map(|(index, item)| {
+            let events = Events::new().click(move |_| item);
+
+            Build::with(Default::default(), events)
+                .block(item)
+                .map(move |item| (index, item))
+        }))
+    }
+
+    #[derive(Debug, PartialEq)]
+    enum Message {
+        Left(usize),
+        Right(String),
+    }
+
+    fn app() -> impl Block<Message = Message> {
+        Build::new().block((
+            Child::map(list(vec!["a", "b"]), |(index, _)| Message::Left(index)),
+            Child::map(list(vec!["c"]), |(_, item): (usize, &str)| Message::Right(item.to_string())),
+        ))
+    }
+
+    #[test]
+    fn map() {
+        let node = harness::render(app());
+        let click = Event::Click(Coordinates { x: 0, y: 0 });
+
+        let message = |path: &[usize]| match node.get(path).unwrap().event(click).pop() {
+            Some(Action::Message(message)) => message,
+            _ => panic!("expected a message"),
+        };
+
+        assert_eq!(Message::Left(1), message(&[1]));
+        assert_eq!(Message::Right("c".to_string()), message(&[2]));
+    }
+}
@@ -0,0 +1,97 @@
+use block::{Child, Walker};
+
+/// A child that is only rebuilt when its inputs change.
+pub struct Memo<K, F> {
+    inputs: K,
+    f: F,
+}
+
+/// Build a child with `f`, reusing the previous render instead while `inputs`
+/// compare equal to the inputs it was built from.
+///
+/// When the child is reused, `f` is not called and the child is neither
+/// walked nor diffed. Everything the child depends on, other than context
+/// values, must therefore be part of `inputs`:
+///
+/// ```ignore
+/// memo(state.items.clone(), move || item_list(&items))
+/// ```
+pub fn memo<K, F, C>(inputs: K, f: F) -> Memo<K, F>
+where
+    K: 'static + Send + PartialEq,
+    F: 'static + FnOnce() -> C,
+{
+    Memo { inputs, f }
+}
+
+impl<K, F, C, M> Child<M> for Memo<K, F>
+where
+    K: 'static + Send + PartialEq,
+    F: 'static + FnOnce() -> C,
+    C: Child<M>,
+    M: 'static + Send,
+{
+    fn walk<T>(self, walker: T) -> T::Walked where T: Walker, T::Message: From<M> {
+        walker.memo::<K, M, C, F>(self.inputs, self.f)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    use ::{State, Reactor};
+    use harness::Harness;
+    use block::{Block, Build};
+    use super::*;
+
+    static BUILDS: AtomicUsize = AtomicUsize::new(0);
+
+    struct Counters {
+        memoized: u32,
+        other: u32,
+    }
+
+    impl State for Counters {
+        type Message = bool;
+
+        fn new(_: Reactor<bool>) -> Self {
+            Counters { memoized: 0, other: 0 }
+        }
+
+        fn reduce(&mut self, memoized: bool) {
+            if memoized {
+                self.memoized += 1;
+            } else {
+                self.other += 1;
+            }
+        }
+    }
+
+    fn app(state: &Counters) -> impl Block<Message = bool> {
+        let memoized = state.memoized;
+
+        Build::new().block((
+            state.other,
+            memo(memoized, move || {
+                BUILDS.fetch_add(1, Ordering::SeqCst);
+                memoized
+            }),
+        ))
+    }
+
+    #[test]
+    fn reuse() {
+        let mut harness = Harness::new(app);
+        assert_eq!("00", harness.render().text());
+
+        harness.send(false);
+        harness.send(false);
+        assert_eq!("20", harness.render().text());
+        assert_eq!(1, BUILDS.load(Ordering::SeqCst));
+
+        harness.send(true);
+        assert_eq!("21", harness.render().text());
+        assert_eq!(2, BUILDS.load(Ordering::SeqCst));
+    }
+}
@@ -1,8 +1,19 @@
+use std::sync::Arc;
+
 use ui::Style;
-use events::{DefaultEvents, EventHandler};
+use events::{self, DefaultEvents, EventHandler};
 
 // TODO: Make this private again.
 pub mod proxy;
+pub mod component;
+pub mod context;
+pub mod embed;
+mod memo;
+mod any;
+mod dynamic;
+mod map;
+pub(crate) mod bake;
+pub(crate) mod scope;
 
 /// Block builder.
 pub struct Build<E> {
@@ -41,6 +52,25 @@ impl<E> Build<E> {
     }
 }
 
+pub use self::memo::{memo, Memo};
+pub use self::any::{AnyBlock, AnyChild};
+pub use self::dynamic::{iter, Iter, Either};
+pub use self::map::Map;
+
+/// Upgrade the messages of a block without wrapping it in another block.
+pub fn upgrade<B, M>(block: B) -> impl Block<Message = M>
+where
+    B: Block,
+    M: 'static + Send + From<B::Message>,
+{
+    let BlockData { data, child } = block.extract();
+
+    BlockData {
+        data: Build::with(data.style, events::Upgrade::new(data.event_handler)),
+        child: proxy::upgrade::Child::new(child),
+    }
+}
+
 pub trait Walker {
     type Message;
     type Walked;
@@ -59,6 +89,43 @@ pub trait Walker {
         E: 'static,
         M: 'static + Send;
 
+    fn component<C>(self, C) -> Self::Walked
+    where
+        C: Mount,
+        Self::Message: From<C::Output>;
+
+    /// Walk a child with a context value available to its descendants.
+    fn provide<T, M, C>(self, value: T, child: C) -> Self::Walked
+    where
+        T: 'static + Send + Clone + PartialEq,
+        C: Child<M>,
+        Self::Message: From<M>,
+        M: 'static + Send;
+
+    /// Build a child from the closest context value of type `T`, and walk it.
+    fn consume<T, M, C, F>(self, F) -> Self::Walked
+    where
+        T: 'static,
+        F: FnOnce(Option<&T>) -> C,
+        C: Child<M>,
+        Self::Message: From<M>,
+        M: 'static + Send;
+
+    /// Walk the child built by `f`, unless `inputs` are unchanged since the
+    /// last render, in which case the previous result may be reused.
+    fn memo<K, M, C, F>(self, inputs: K, f: F) -> Self::Walked
+    where
+        K: 'static + Send + PartialEq,
+        F: FnOnce() -> C,
+        C: Child<M>,
+        Self::Message: From<M>,
+        M: 'static + Send;
+
+    fn erased<M>(self, AnyChild<M>) -> Self::Walked
+    where
+        Self::Message: From<M>,
+        M: 'static + Send;
+
     fn text(self, text: &str) -> Self::Walked;
 
     fn empty(self) -> Self::Walked;
@@ -66,6 +133,18 @@ pub trait Walker {
 
 pub trait Child<M>: 'static {
     fn walk<T>(self, T) -> T::Walked where T: Walker, T::Message: From<M>;
+
+    /// Transform the messages of a child with a closure.
+    ///
+    /// This is an associated function rather than a method, so that it does
+    /// not clash with `Block::map`. Call it as `Child::map(child, f)`.
+    fn map<N, F>(child: Self, f: F) -> Map<Self, F, M>
+    where
+        Self: Sized,
+        F: 'static + Send + Sync + Fn(M) -> N,
+    {
+        Map::new(child, Arc::new(f))
+    }
 }
 
 impl<M> Child<M> for () {
@@ -173,6 +252,27 @@ pub trait Consolidator {
     fn child<M, C>(&mut self, C) where C: Child<M>, Self::Message: From<M>, M: 'static;
 }
 
+/// A child with state that persists between renders.
+///
+/// See `component::stateful`.
+pub trait Mount: 'static {
+    type State: 'static + Send;
+    /// Messages handled by the state.
+    type Message: 'static + Send;
+    /// Messages passed on to the parent.
+    type Output: 'static + Send;
+
+    /// A key identifying the child among its siblings, instead of its index.
+    fn key(&self) -> Option<u64>;
+
+    /// Create the state. Only called when the child is first rendered.
+    fn init(&mut self) -> Self::State;
+
+    fn view<T>(&self, &Self::State, T) -> T::Walked where T: Walker, T::Message: From<Self::Message>;
+
+    fn reduce(&mut Self::State, Self::Message) -> Option<Self::Output>;
+}
+
 pub trait Group<M> {
     fn consolidate<C>(self, C)
     where
@@ -186,8 +286,43 @@ pub trait Block: 'static {
     type Child: Child<Self::Message>;
 
     fn extract(self) -> BlockData<Self::EventHandler, Self::Child>;
+
+    /// Transform the messages of this block with a closure.
+    ///
+    /// Unlike upgrading through `From`, the closure can capture values, such
+    /// as the index of a row:
+    ///
+    /// ```ignore
+    /// row(item).map(move |message| Message::Row(index, message))
+    /// ```
+    fn map<N, F>(self, f: F) -> Mapped<Self, F>
+    where
+        Self: Sized,
+        F: 'static + Send + Sync + Fn(Self::Message) -> N,
+        N: 'static + Send,
+    {
+        let BlockData { data, child } = self.extract();
+        let f = Arc::new(f);
+
+        BlockData {
+            data: Build::with(data.style, events::Map::new(data.event_handler, f.clone())),
+            child: Map::new(child, f),
+        }
+    }
+
+    /// Erase the type of this block, so that blocks of different types with
+    /// the same message type can be used interchangeably.
+    fn boxed(self) -> AnyBlock<Self::Message> where Self: Sized {
+        AnyBlock::new(self)
+    }
 }
 
+/// A block with its messages transformed by a closure.
+pub type Mapped<B, F> = BlockData<
+    events::Map<<B as Block>::EventHandler, F>,
+    Map<<B as Block>::Child, F, <B as Block>::Message>
+>;
+
 pub struct BlockData<E, C> {
     pub data: Build<E>,
     pub child: C,
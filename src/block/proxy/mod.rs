@@ -1,9 +1,9 @@
 use std::marker::PhantomData;
 
 use events::{self, EventHandler};
-use block::{Build, Child, Consolidator, Group, Walker};
+use block::{AnyChild, Build, Child, Consolidator, Group, Mount, Walker};
 
-mod upgrade;
+pub(crate) mod upgrade;
 
 /// A walker implementation that wraps another walker and upgrades all children.
 pub struct Walk<T, M> {
@@ -51,6 +51,54 @@ where
         self.walker.block(data, child)
     }
 
+    fn component<C>(self, component: C) -> Self::Walked
+    where
+        C: Mount,
+        Self::Message: From<C::Output>,
+    {
+        self.walker.component(upgrade::Mount::<C, MI>::new(component))
+    }
+
+    fn provide<V, M, C>(self, value: V, child: C) -> Self::Walked
+    where
+        V: 'static + Send + Clone + PartialEq,
+        C: Child<M>,
+        Self::Message: From<M>,
+        M: 'static + Send,
+    {
+        self.walker.provide(value, upgrade::Child::<C, M, MI>::new(child))
+    }
+
+    fn consume<V, M, C, F>(self, f: F) -> Self::Walked
+    where
+        V: 'static,
+        F: FnOnce(Option<&V>) -> C,
+        C: Child<M>,
+        Self::Message: From<M>,
+        M: 'static + Send,
+    {
+        self.walker.consume(move |value: Option<&V>| upgrade::Child::<C, M, MI>::new(f(value)))
+    }
+
+    fn memo<K, M, C, F>(self, inputs: K, f: F) -> Self::Walked
+    where
+        K: 'static + Send + PartialEq,
+        F: FnOnce() -> C,
+        C: Child<M>,
+        Self::Message: From<M>,
+        M: 'static + Send,
+    {
+        self.walker.memo(inputs, move || upgrade::Child::<C, M, MI>::new(f()))
+    }
+
+    fn erased<M>(self, child: AnyChild<M>) -> Self::Walked
+    where
+        Self::Message: From<M>,
+        M: 'static + Send,
+    {
+        self.walker.erased(child.upgrade::<MI>())
+    }
+
     fn text(self, text: &str) -> Self::Walked {
         self.walker.text(text)
     }
@@ -55,3 +55,45 @@ where
         self.child.walk(super::Walk::new(walker))
     }
 }
+
+/// Upgrade a stateful child to output target messages.
+pub struct Mount<C, MT> {
+    component: C,
+    _target: PhantomData<MT>,
+}
+
+impl<C, MT> Mount<C, MT> {
+    pub fn new(component: C) -> Self {
+        Self { component, _target: PhantomData }
+    }
+}
+
+impl<C, MT> block::Mount for Mount<C, MT>
+where
+    C: block::Mount,
+    MT: 'static + Send + From<C::Output>,
+{
+    type State = C::State;
+    type Message = C::Message;
+    type Output = MT;
+
+    fn key(&self) -> Option<u64> {
+        self.component.key()
+    }
+
+    fn init(&mut self) -> Self::State {
+        self.component.init()
+    }
+
+    fn view<T>(&self, state: &Self::State, walker: T) -> T::Walked
+    where
+        T: Walker,
+        T::Message: From<Self::Message>,
+    {
+        self.component.view(state, walker)
+    }
+
+    fn reduce(state: &mut Self::State, message: Self::Message) -> Option<MT> {
+        C::reduce(state, message).map(MT::from)
+    }
+}
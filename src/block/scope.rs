@@ -0,0 +1,241 @@
+//! State that lives across renders, keyed by position in the block tree.
+
+use std::any::{Any, TypeId};
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::collections::hash_map::DefaultHasher;
+use std::hash::{Hash, Hasher};
+use std::rc::Rc;
+use std::sync::{Arc, Mutex};
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+/// A number that is never returned twice.
+pub fn unique() -> u64 {
+    static NEXT: AtomicUsize = AtomicUsize::new(0);
+
+    NEXT.fetch_add(1, Ordering::SeqCst) as u64
+}
+
+/// Hash a key given to a child, for use with `Scope::keyed`.
+///
+/// A keyed child is found by its key instead of its index among its siblings,
+/// so the state kept for it follows it when siblings are added, removed or
+/// reordered.
+pub fn key<K>(key: K) -> u64 where K: Hash {
+    let mut hasher = DefaultHasher::new();
+    key.hash(&mut hasher);
+    hasher.finish()
+}
+
+#[derive(Clone, PartialEq, Eq, Hash, Debug)]
+enum Segment {
+    Index(usize),
+    Key(u64),
+}
+
+/// The position of a child in the block tree.
+#[derive(Clone, PartialEq, Eq, Hash, Debug)]
+pub struct Path(Vec<Segment>);
+
+impl Path {
+    fn root() -> Self {
+        Path(Vec::new())
+    }
+
+    fn child(&self, index: usize) -> Self {
+        let mut segments = self.0.clone();
+        segments.push(Segment::Index(index));
+        Path(segments)
+    }
+
+    /// Replace the last index with a key, so the position survives reordering.
+    fn keyed(&self, key: u64) -> Self {
+        let mut segments = self.0.clone();
+        segments.pop();
+        segments.push(Segment::Key(key));
+        Path(segments)
+    }
+
+    fn starts_with(&self, prefix: &Path) -> bool {
+        self.0.starts_with(&prefix.0)
+    }
+}
+
+struct Slot {
+    value: Box<Any + Send>,
+    live: bool,
+}
+
+/// Values kept between renders.
+///
+/// A value is dropped by `sweep` if nothing asked for it during the last
+/// render, which happens when its part of the tree is no longer rendered.
+pub struct Store {
+    slots: RefCell<HashMap<(Path, TypeId), Slot>>,
+}
+
+impl Store {
+    pub fn new() -> Self {
+        Store { slots: RefCell::new(HashMap::new()) }
+    }
+
+    /// Get the value of type `T` at a path, creating it if it is missing.
+    fn get<T, I>(&self, path: &Path, init: I) -> Arc<Mutex<T>>
+    where
+        T: 'static + Send,
+        I: FnOnce() -> T,
+    {
+        let key = (path.clone(), TypeId::of::<T>());
+
+        if let Some(slot) = self.slots.borrow_mut().get_mut(&key) {
+            if let Some(value) = slot.value.downcast_ref::<Arc<Mutex<T>>>() {
+                slot.live = true;
+                return value.clone();
+            }
+        }
+
+        let value = Arc::new(Mutex::new(init()));
+
+        self.slots.borrow_mut().insert(key, Slot {
+            value: Box::new(value.clone()),
+            live: true,
+        });
+
+        value
+    }
+
+    /// Keep every value at or below a path, without rendering it.
+    fn keep(&self, path: &Path) {
+        for (&(ref slot_path, _), slot) in self.slots.borrow_mut().iter_mut() {
+            if slot_path.starts_with(path) {
+                slot.live = true;
+            }
+        }
+    }
+
+    /// Drop every value that was not used since the last sweep.
+    pub fn sweep(&self) {
+        let mut slots = self.slots.borrow_mut();
+
+        slots.retain(|_, slot| slot.live);
+
+        for slot in slots.values_mut() {
+            slot.live = false;
+        }
+    }
+}
+
+/// A context value, linked to the values provided further up the tree.
+struct Frame {
+    value: Box<Any>,
+    /// Changes whenever the provided value does.
+    version: u64,
+    parent: Option<Rc<Frame>>,
+}
+
+/// Everything visible to a child being walked: the store, the position of the
+/// child, and the context values provided by its ancestors.
+#[derive(Clone)]
+pub struct Scope<'a> {
+    store: &'a Store,
+    path: Path,
+    context: Option<Rc<Frame>>,
+}
+
+impl<'a> Scope<'a> {
+    /// The scope of the root block.
+    pub fn new(store: &'a Store) -> Self {
+        Scope {
+            store,
+            path: Path::root(),
+            context: None,
+        }
+    }
+
+    /// The scope of the child at an index.
+    pub fn child(&self, index: usize) -> Self {
+        Scope {
+            path: self.path.child(index),
+            .. self.clone()
+        }
+    }
+
+    /// This scope, identified by a key instead of its index.
+    pub fn keyed(&self, key: u64) -> Self {
+        Scope {
+            path: self.path.keyed(key),
+            .. self.clone()
+        }
+    }
+
+    /// The scope of the only child of a provider, with a context value
+    /// shadowing any value of the same type.
+    pub fn provide<T>(&self, value: T) -> Self where T: 'static + Send + Clone + PartialEq {
+        let previous = self.state(|| (value.clone(), unique()));
+
+        let version = {
+            let mut previous = previous.lock().unwrap();
+
+            if previous.0 != value {
+                *previous = (value.clone(), unique());
+            }
+
+            previous.1
+        };
+
+        let frame = Frame {
+            value: Box::new(value),
+            version,
+            parent: self.context.clone(),
+        };
+
+        Scope {
+            context: Some(Rc::new(frame)),
+            .. self.child(0)
+        }
+    }
+
+    /// The versions of every context value in scope, nearest first.
+    pub fn versions(&self) -> Vec<u64> {
+        let mut versions = Vec::new();
+        let mut frame = self.context.as_ref();
+
+        while let Some(current) = frame {
+            versions.push(current.version);
+            frame = current.parent.as_ref();
+        }
+
+        versions
+    }
+
+    /// The closest context value of type `T`.
+    pub fn context<T>(&self) -> Option<&T> where T: 'static {
+        let mut frame = self.context.as_ref();
+
+        while let Some(current) = frame {
+            if let Some(value) = current.value.downcast_ref::<T>() {
+                return Some(value);
+            }
+
+            frame = current.parent.as_ref();
+        }
+
+        None
+    }
+
+    /// The value of type `T` owned by this position.
+    pub fn state<T, I>(&self, init: I) -> Arc<Mutex<T>>
+    where
+        T: 'static + Send,
+        I: FnOnce() -> T,
+    {
+        self.store.get(&self.path, init)
+    }
+
+    /// Keep every value owned by this position and its descendants, when they
+    /// are not walked.
+    pub fn keep(&self) {
+        self.store.keep(&self.path);
+    }
+}
+
@@ -1,6 +1,10 @@
 use std::marker::PhantomData;
 use std::ops::Sub;
+use std::sync::Arc;
 
+use ::Update;
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
 pub enum Event {
     Render,
     Click(Coordinates),
@@ -8,6 +12,57 @@ pub enum Event {
     MouseUp(Coordinates, Button),
 }
 
+/// The kind of an event, without its data.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Kind {
+    Render,
+    Click,
+    MouseDown,
+    MouseUp,
+}
+
+impl Event {
+    pub fn kind(&self) -> Kind {
+        match *self {
+            Event::Render => Kind::Render,
+            Event::Click(..) => Kind::Click,
+            Event::MouseDown(..) => Kind::MouseDown,
+            Event::MouseUp(..) => Kind::MouseUp,
+        }
+    }
+}
+
+/// Deferred work, run by the runtime in order with messages.
+///
+/// The message it returns, if any, is reduced afterwards.
+pub type Effect<M> = Box<FnOnce() -> Option<M> + Send>;
+
+/// What the runtime does in response to an event.
+pub enum Action<M> {
+    /// Reduce a message.
+    Message(M),
+    /// Run an effect, then re-render.
+    Effect(Effect<M>),
+}
+
+impl<M> Action<M> where M: 'static + Send {
+    /// Transform the message this action produces.
+    pub fn map<N, F>(self, f: F) -> Action<N> where F: 'static + Send + FnOnce(M) -> N {
+        match self {
+            Action::Message(message) => Action::Message(f(message)),
+            Action::Effect(effect) => Action::Effect(Box::new(move || effect().map(f))),
+        }
+    }
+
+    /// Hand this action to an update target.
+    pub fn dispatch<U>(self, update: &U) where U: Update<Message = M> {
+        match self {
+            Action::Message(message) => update.reduce(message),
+            Action::Effect(effect) => update.effect(effect),
+        }
+    }
+}
+
 pub struct Upgrade<E, M> {
     handler: E,
     _message: PhantomData<M>,
@@ -20,8 +75,12 @@ where
 {
     type Message = M;
 
-    fn event(&self, event: Event) -> Option<Self::Message> {
-        self.handler.event(event).map(M::from)
+    fn event(&self, event: Event) -> Vec<Self::Message> {
+        self.handler.event(event).into_iter().map(M::from).collect()
+    }
+
+    fn handles(&self, kind: Kind) -> bool {
+        self.handler.handles(kind)
     }
 }
 
@@ -38,33 +97,145 @@ where
     }
 }
 
+/// Transforms the messages of a handler with a closure.
+pub struct Map<E, F> {
+    handler: E,
+    f: Arc<F>,
+}
+
+impl<E, F> Map<E, F> {
+    pub fn new(handler: E, f: Arc<F>) -> Self {
+        Map { handler, f }
+    }
+}
+
+impl<E, F, N> EventHandler for Map<E, F>
+where
+    E: EventHandler,
+    F: Send + Sync + Fn(E::Message) -> N,
+    N: 'static + Send,
+{
+    type Message = N;
+
+    fn event(&self, event: Event) -> Vec<N> {
+        self.handler.event(event).into_iter().map(|message| (self.f)(message)).collect()
+    }
+
+    fn handles(&self, kind: Kind) -> bool {
+        self.handler.handles(kind)
+    }
+}
+
 pub trait EventHandler: Send {
     type Message: 'static + Send;
 
-    fn event(&self, event: Event) -> Option<Self::Message>;
+    /// Handle an event, returning the messages to reduce in order.
+    ///
+    /// This returns a `Vec` rather than an `Option`, so that one event can
+    /// send several messages. A handler that produces an `Option` returns
+    /// `option.into_iter().collect()`, with an empty `Vec` ignoring the event.
+    fn event(&self, event: Event) -> Vec<Self::Message>;
+
+    /// Whether this handler may respond to events of a kind.
+    ///
+    /// Backends only listen for the kinds of events a handler responds to.
+    /// By default, a handler receives every kind of event.
+    fn handles(&self, _kind: Kind) -> bool {
+        true
+    }
+}
+
+impl<M> EventHandler for Box<EventHandler<Message = M>> where M: 'static + Send {
+    type Message = M;
+
+    fn event(&self, event: Event) -> Vec<M> {
+        (**self).event(event)
+    }
+
+    fn handles(&self, kind: Kind) -> bool {
+        (**self).handles(kind)
+    }
+}
+
+/// A handler for one kind of event, called with the event data `A`.
+pub trait Respond<A, M> {
+    fn respond(&self, args: A) -> Vec<M>;
+}
+
+/// Responds to every event with one message.
+pub struct One<H>(H);
+
+/// Responds to an event with a message, or ignores it.
+pub struct Filter<H>(H);
+
+/// Responds to an event with any number of messages.
+pub struct Many<H>(H);
+
+macro_rules! impl_respond {
+    ($(($A:ty, ($($arg:ident: $T:ty),*), $args:pat)),*) => {$(
+        impl<M, H> Respond<$A, M> for One<H> where H: Fn($($T),*) -> M {
+            fn respond(&self, $args: $A) -> Vec<M> {
+                vec![(self.0)($($arg),*)]
+            }
+        }
+
+        impl<M, H> Respond<$A, M> for Filter<H> where H: Fn($($T),*) -> Option<M> {
+            fn respond(&self, $args: $A) -> Vec<M> {
+                (self.0)($($arg),*).into_iter().collect()
+            }
+        }
+
+        impl<M, H, I> Respond<$A, M> for Many<H> where H: Fn($($T),*) -> I, I: IntoIterator<Item = M> {
+            fn respond(&self, $args: $A) -> Vec<M> {
+                (self.0)($($arg),*).into_iter().collect()
+            }
+        }
+    )*}
+}
+
+impl_respond! {
+    ((), (), ()),
+    (Coordinates, (coordinates: Coordinates), coordinates),
+    ((Coordinates, Button), (coordinates: Coordinates, button: Button), (coordinates, button))
+}
+
+fn respond<R, A, M>(handler: &Option<R>, args: A) -> Vec<M> where R: Respond<A, M> {
+    match *handler {
+        Some(ref handler) => handler.respond(args),
+        None => Vec::new(),
+    }
 }
 
 impl<M, R, C, D, U> EventHandler for Events<M, R, C, D, U>
 where
-    R: Send + Fn() -> M,
-    C: Send + Fn(Coordinates) -> M,
-    D: Send + Fn(Coordinates, Button) -> M,
-    U: Send + Fn(Coordinates, Button) -> M,
+    R: Send + Respond<(), M>,
+    C: Send + Respond<Coordinates, M>,
+    D: Send + Respond<(Coordinates, Button), M>,
+    U: Send + Respond<(Coordinates, Button), M>,
     M: 'static + Send,
 {
     type Message = M;
 
-    fn event(&self, event: Event) -> Option<Self::Message> {
+    fn event(&self, event: Event) -> Vec<Self::Message> {
         match event {
-            Event::Render => self.render.as_ref().map(|r| r()),
-            Event::Click(coordinates) => self.click.as_ref().map(|h| h(coordinates)),
-            Event::MouseDown(coordinates, button) => self.down.as_ref().map(|h| h(coordinates, button)),
-            Event::MouseUp(coordinates, button) => self.up.as_ref().map(|h| h(coordinates, button)),
+            Event::Render => respond(&self.render, ()),
+            Event::Click(coordinates) => respond(&self.click, coordinates),
+            Event::MouseDown(coordinates, button) => respond(&self.down, (coordinates, button)),
+            Event::MouseUp(coordinates, button) => respond(&self.up, (coordinates, button)),
+        }
+    }
+
+    fn handles(&self, kind: Kind) -> bool {
+        match kind {
+            Kind::Render => self.render.is_some(),
+            Kind::Click => self.click.is_some(),
+            Kind::MouseDown => self.down.is_some(),
+            Kind::MouseUp => self.up.is_some(),
         }
     }
 }
 
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
 pub struct Coordinates {
     pub x: u32,
     pub y: u32,
@@ -96,32 +267,32 @@ impl Sub<Offset> for Coordinates {
     }
 }
 
-#[derive(Debug, Clone, Copy, PartialEq)]
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
 pub enum Button {
     Left,
     Right,
     Middle,
 }
 
-pub struct Events<M, R, C, D, U>
-where
-    R: Fn() -> M,
-    C: Fn(Coordinates) -> M,
-    D: Fn(Coordinates, Button) -> M,
-    U: Fn(Coordinates, Button) -> M
-{
+/// An event handler built from one closure per kind of event.
+///
+/// Each kind of event can be handled by a closure returning one message, an
+/// optional message (the `_filter` variants), or any number of messages (the
+/// `_many` variants). Messages are reduced in the order they are returned.
+pub struct Events<M, R, C, D, U> {
     render: Option<R>,
     click: Option<C>,
     down: Option<D>,
     up: Option<U>,
+    _message: PhantomData<M>,
 }
 
 pub type DefaultEvents<M> = Events<
     M,
-    fn() -> M,
-    fn(Coordinates) -> M,
-    fn(Coordinates, Button) -> M,
-    fn(Coordinates, Button) -> M
+    One<fn() -> M>,
+    One<fn(Coordinates) -> M>,
+    One<fn(Coordinates, Button) -> M>,
+    One<fn(Coordinates, Button) -> M>
 >;
 
 impl<M> DefaultEvents<M> {
@@ -131,59 +302,125 @@ impl<M> DefaultEvents<M> {
             click: None,
             down: None,
             up: None,
+            _message: PhantomData,
         }
     }
 }
 
-impl<M, R, C, D, U> Events<M, R, C, D, U>
-where
-    R: Fn() -> M,
-    C: Fn(Coordinates) -> M,
-    D: Fn(Coordinates, Button) -> M,
-    U: Fn(Coordinates, Button) -> M
-{
-    pub fn click<H>(self, handler: H) -> Events<M, R, H, D, U>
+impl<M, R, C, D, U> Events<M, R, C, D, U> {
+    pub fn click<H>(self, handler: H) -> Events<M, R, One<H>, D, U>
         where H: Fn(Coordinates) -> M
     {
+        self.on_click(One(handler))
+    }
+
+    pub fn click_filter<H>(self, handler: H) -> Events<M, R, Filter<H>, D, U>
+        where H: Fn(Coordinates) -> Option<M>
+    {
+        self.on_click(Filter(handler))
+    }
+
+    pub fn click_many<H, I>(self, handler: H) -> Events<M, R, Many<H>, D, U>
+        where H: Fn(Coordinates) -> I, I: IntoIterator<Item = M>
+    {
+        self.on_click(Many(handler))
+    }
+
+    pub fn mouse_down<H>(self, handler: H) -> Events<M, R, C, One<H>, U>
+        where H: Fn(Coordinates, Button) -> M
+    {
+        self.on_mouse_down(One(handler))
+    }
+
+    pub fn mouse_down_filter<H>(self, handler: H) -> Events<M, R, C, Filter<H>, U>
+        where H: Fn(Coordinates, Button) -> Option<M>
+    {
+        self.on_mouse_down(Filter(handler))
+    }
+
+    pub fn mouse_down_many<H, I>(self, handler: H) -> Events<M, R, C, Many<H>, U>
+        where H: Fn(Coordinates, Button) -> I, I: IntoIterator<Item = M>
+    {
+        self.on_mouse_down(Many(handler))
+    }
+
+    pub fn mouse_up<H>(self, handler: H) -> Events<M, R, C, D, One<H>>
+        where H: Fn(Coordinates, Button) -> M
+    {
+        self.on_mouse_up(One(handler))
+    }
+
+    pub fn mouse_up_filter<H>(self, handler: H) -> Events<M, R, C, D, Filter<H>>
+        where H: Fn(Coordinates, Button) -> Option<M>
+    {
+        self.on_mouse_up(Filter(handler))
+    }
+
+    pub fn mouse_up_many<H, I>(self, handler: H) -> Events<M, R, C, D, Many<H>>
+        where H: Fn(Coordinates, Button) -> I, I: IntoIterator<Item = M>
+    {
+        self.on_mouse_up(Many(handler))
+    }
+
+    pub fn render<H>(self, handler: H) -> Events<M, One<H>, C, D, U>
+    where
+        H: Fn() -> M,
+    {
+        self.on_render(One(handler))
+    }
+
+    pub fn render_filter<H>(self, handler: H) -> Events<M, Filter<H>, C, D, U>
+    where
+        H: Fn() -> Option<M>,
+    {
+        self.on_render(Filter(handler))
+    }
+
+    pub fn render_many<H, I>(self, handler: H) -> Events<M, Many<H>, C, D, U>
+    where
+        H: Fn() -> I,
+        I: IntoIterator<Item = M>,
+    {
+        self.on_render(Many(handler))
+    }
+
+    fn on_click<H>(self, handler: H) -> Events<M, R, H, D, U> {
         Events {
             render: self.render,
             click: Some(handler),
             down: self.down,
             up: self.up,
+            _message: PhantomData,
         }
     }
 
-    pub fn mouse_down<H>(self, handler: H) -> Events<M, R, C, H, U>
-        where H: Fn(Coordinates, Button) -> M
-    {
+    fn on_mouse_down<H>(self, handler: H) -> Events<M, R, C, H, U> {
         Events {
             render: self.render,
             click: self.click,
             down: Some(handler),
             up: self.up,
+            _message: PhantomData,
         }
     }
 
-    pub fn mouse_up<H>(self, handler: H) -> Events<M, R, C, D, H>
-        where H: Fn(Coordinates, Button) -> M
-    {
+    fn on_mouse_up<H>(self, handler: H) -> Events<M, R, C, D, H> {
         Events {
             render: self.render,
             click: self.click,
             down: self.down,
             up: Some(handler),
+            _message: PhantomData,
         }
     }
 
-    pub fn render<H>(self, handler: H) -> Events<M, H, C, D, U>
-    where
-        H: Fn() -> M,
-    {
+    fn on_render<H>(self, handler: H) -> Events<M, H, C, D, U> {
         Events {
             render: Some(handler),
             click: self.click,
             down: self.down,
             up: self.up,
+            _message: PhantomData,
         }
     }
 }
@@ -206,9 +443,9 @@ mod test {
 
         let button = Button::Left;
 
-        assert_eq!(Some(1), events.event(Event::Click(coordinates)));
-        assert_eq!(Some(2), events.event(Event::MouseDown(coordinates, button)));
-        assert_eq!(Some(3), events.event(Event::MouseUp(coordinates, button)));
+        assert_eq!(vec![1], events.event(Event::Click(coordinates)));
+        assert_eq!(vec![2], events.event(Event::MouseDown(coordinates, button)));
+        assert_eq!(vec![3], events.event(Event::MouseUp(coordinates, button)));
     }
 
     #[test]
@@ -216,7 +453,7 @@ mod test {
         let events = Events::new()
             .click(|Coordinates { x, y }| (x, y));
 
-        assert_eq!(Some((1, 2)), events.event(Event::Click(Coordinates { x: 1, y: 2 })));
+        assert_eq!(vec![(1, 2)], events.event(Event::Click(Coordinates { x: 1, y: 2 })));
     }
 
     #[test]
@@ -224,7 +461,7 @@ mod test {
         let events = Events::new()
             .mouse_down(|Coordinates { x, y }, button| (x, y, button));
 
-        assert_eq!(Some((1, 2, Button::Left)), events.event(Event::MouseDown(Coordinates { x: 1, y: 2 }, Button::Left)));
+        assert_eq!(vec![(1, 2, Button::Left)], events.event(Event::MouseDown(Coordinates { x: 1, y: 2 }, Button::Left)));
     }
 
     #[test]
@@ -232,6 +469,38 @@ mod test {
         let events = Events::new()
             .mouse_up(|Coordinates { x, y }, button| (x, y, button));
 
-        assert_eq!(Some((1, 2, Button::Left)), events.event(Event::MouseUp(Coordinates { x: 1, y: 2 }, Button::Left)));
+        assert_eq!(vec![(1, 2, Button::Left)], events.event(Event::MouseUp(Coordinates { x: 1, y: 2 }, Button::Left)));
+    }
+
+    #[test]
+    fn filter_and_many() {
+        let events = Events::new()
+            .mouse_down_filter(|_, button| if button == Button::Right { Some(0) } else { None })
+            .mouse_up_many(|Coordinates { x, y }, _| vec![x, y]);
+
+        let coordinates = Coordinates { x: 1, y: 2 };
+
+        assert!(events.event(Event::MouseDown(coordinates, Button::Left)).is_empty());
+        assert_eq!(vec![0], events.event(Event::MouseDown(coordinates, Button::Right)));
+        assert_eq!(vec![1, 2], events.event(Event::MouseUp(coordinates, Button::Left)));
+
+        assert!(events.handles(Kind::MouseDown));
+        assert!(!events.handles(Kind::Click));
+    }
+
+    #[test]
+    fn handles_by_default() {
+        struct Clicks;
+
+        impl EventHandler for Clicks {
+            type Message = ();
+
+            fn event(&self, _: Event) -> Vec<()> {
+                vec![()]
+            }
+        }
+
+        assert!(Clicks.handles(Kind::Click));
+        assert!(Clicks.handles(Kind::Render));
     }
 }
@@ -0,0 +1,376 @@
+//! A native runtime for testing apps without a browser.
+//!
+//! The harness walks blocks the same way the web backend does, but renders
+//! into a plain `Node` tree that can be inspected and clicked on.
+
+use std::marker::PhantomData;
+use std::sync::Arc;
+
+use ::{State, Reactor, Update};
+use ui::Style;
+use queue::{Queue, Job};
+use record::{Session, Inert};
+use middleware::Pipeline;
+use block::{Block, BlockData, Child};
+use block::bake::{Act, BakedChild, BakedWalker};
+use block::scope::{Scope, Store};
+use events::{Action, Effect, Event, EventHandler};
+
+/// A rendered node.
+pub enum Node<M> {
+    Text(String),
+    Element {
+        style: Style,
+        events: Box<EventHandler<Message = Action<M>>>,
+        children: Vec<Node<M>>,
+    },
+}
+
+impl<M> Node<M> where M: 'static + Send {
+    /// The style of an element, or `None` for text.
+    pub fn style(&self) -> Option<&Style> {
+        match *self {
+            Node::Text(..) => None,
+            Node::Element { ref style, .. } => Some(style),
+        }
+    }
+
+    /// The child nodes of an element, which is empty for text.
+    pub fn children(&self) -> &[Node<M>] {
+        match *self {
+            Node::Text(..) => &[],
+            Node::Element { ref children, .. } => children,
+        }
+    }
+
+    /// Find a descendant by its child indices.
+    pub fn get(&self, path: &[usize]) -> Option<&Node<M>> {
+        match path.split_first() {
+            Some((&index, rest)) => self.children().get(index).and_then(|child| child.get(rest)),
+            None => Some(self),
+        }
+    }
+
+    /// The concatenated text of this node and its descendants.
+    pub fn text(&self) -> String {
+        match *self {
+            Node::Text(ref text) => text.clone(),
+            Node::Element { ref children, .. } => children.iter().map(Node::text).collect(),
+        }
+    }
+
+    /// Pass an event to this node's handler.
+    pub fn event(&self, event: Event) -> Vec<Action<M>> {
+        match *self {
+            Node::Text(..) => Vec::new(),
+            Node::Element { ref events, .. } => events.event(event),
+        }
+    }
+}
+
+impl<M> BakedChild<M> where M: 'static + Send {
+    fn to_nodes(self) -> Vec<Node<M>> {
+        match self {
+            BakedChild::Text(t) => vec![Node::Text(t)],
+            BakedChild::Empty => vec![],
+            BakedChild::Group(children) =>
+                children
+                    .into_iter()
+                    .flat_map(BakedChild::to_nodes)
+                    .collect(),
+            BakedChild::Element { child, style, events } => vec![
+                Node::Element {
+                    style,
+                    events: Box::new(events),
+                    children: child.to_nodes(),
+                },
+            ],
+            BakedChild::Memo { child, .. } => child.bake().to_nodes(),
+        }
+    }
+}
+
+/// Render a block into a node tree.
+///
+/// Components are rendered with their initial state, and messages sent by
+/// embedded apps are dropped.
+pub fn render<B>(block: B) -> Node<B::Message> where B: Block {
+    render_in(block, &Store::new(), Box::new(Inert::new()))
+}
+
+fn render_in<B>(block: B, store: &Store, update: Box<Update<Message = B::Message>>) -> Node<B::Message>
+where
+    B: Block,
+{
+    let BlockData { child, data } = block.extract();
+    let walker = BakedWalker::<B::Message>::new(Scope::new(store), update);
+
+    let node = Node::Element {
+        style: data.style,
+        events: Box::new(Act::new(data.event_handler)),
+        children: child.walk(walker).to_nodes(),
+    };
+
+    store.sweep();
+    node
+}
+
+/// Queues messages sent through a reactor until the harness processes them.
+struct Enqueue<M> {
+    queue: Arc<Queue<Job<M>>>,
+}
+
+impl<M> Update for Enqueue<M> where M: Send + 'static {
+    type Message = M;
+
+    fn reduce(&self, message: M) {
+        self.queue.push(Job::Message(message));
+    }
+
+    fn clone(&self) -> Box<Update<Message = M>> {
+        Box::new(Enqueue { queue: self.queue.clone() })
+    }
+
+    fn effect(&self, effect: Effect<M>) {
+        self.queue.push(Job::Effect(effect));
+    }
+}
+
+/// A native app runtime.
+///
+/// Messages are processed in the same order as in the web backend, including
+/// messages sent through the reactor while another message is being handled.
+pub struct Harness<S, F, B> where S: State {
+    state: S,
+    app: F,
+    queue: Arc<Queue<Job<S::Message>>>,
+    update: Box<Update<Message = S::Message>>,
+    store: Store,
+    _block: PhantomData<B>,
+}
+
+impl<S, F, B> Harness<S, F, B>
+where
+    B: Block,
+    S: State<Message = B::Message>,
+    F: Fn(&S) -> B,
+{
+    /// Start an app.
+    pub fn new(app: F) -> Self {
+        Self::start(app, S::new, Pipeline::new())
+    }
+
+    /// Start an app with every message passing through a pipeline.
+    pub fn piped(pipeline: Pipeline<S::Message>, app: F) -> Self {
+        Self::start(app, S::new, pipeline)
+    }
+
+    /// Start an app from a replayed session.
+    pub fn replay(session: &Session<S::Message>, app: F) -> Self where S::Message: Clone {
+        Self::start(app, |reactor| session.replay(reactor), Pipeline::new())
+    }
+
+    fn start<I>(app: F, init: I, pipeline: Pipeline<S::Message>) -> Self
+    where
+        I: FnOnce(Reactor<S::Message>) -> S,
+    {
+        let queue = Arc::new(Queue::new());
+        let update = pipeline.share().wrap(Enqueue { queue: queue.clone() });
+        let state = init(Reactor::new(Update::clone(&update)));
+
+        let mut harness = Harness {
+            state,
+            app,
+            queue,
+            update: Box::new(update),
+            store: Store::new(),
+            _block: PhantomData,
+        };
+
+        // Process anything sent from `State::new`.
+        harness.flush();
+        harness
+    }
+
+    pub fn state(&self) -> &S {
+        &self.state
+    }
+
+    /// Render the app in its current state.
+    ///
+    /// Rendering can queue messages, such as those of embedded apps that are
+    /// rendered for the first time or given new props. Like the web backend,
+    /// the harness processes them and renders again until nothing is queued.
+    pub fn render(&mut self) -> Node<S::Message> {
+        loop {
+            let node = render_in((self.app)(&self.state), &self.store, Update::clone(&self.update));
+
+            if self.queue.is_empty() {
+                return node;
+            }
+
+            self.flush();
+        }
+    }
+
+    /// Send a message to the app.
+    pub fn send(&mut self, message: S::Message) {
+        self.update.reduce(message);
+        self.flush();
+    }
+
+    /// Deliver an event to the node at a path, as if it came from the user.
+    ///
+    /// Returns `false` if there is no node at the path.
+    pub fn event(&mut self, path: &[usize], event: Event) -> bool {
+        let actions = match self.render().get(path) {
+            Some(node) => node.event(event),
+            None => return false,
+        };
+
+        self.queue.push(Job::Event(event));
+
+        for action in actions {
+            action.dispatch(&self.update);
+        }
+
+        self.flush();
+        true
+    }
+
+    fn flush(&mut self) {
+        let queue = self.queue.clone();
+        let state = &mut self.state;
+
+        queue.drain(|job| match job {
+            Job::Event(event) => state.event(&event),
+            Job::Message(message) => state.reduce(message),
+            Job::Effect(effect) => if let Some(message) = effect() {
+                queue.push(Job::Message(message));
+            },
+            // Rendering is done on demand.
+            Job::Render => {}
+        });
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use events::{Events, Coordinates};
+    use ui::text::{Wrap, Overflow};
+    use block::Build;
+    use record::Recording;
+    use super::*;
+
+    struct Counter {
+        value: i32,
+        reactor: Reactor<i32>,
+    }
+
+    impl State for Counter {
+        type Message = i32;
+
+        fn new(reactor: Reactor<i32>) -> Self {
+            reactor.send(10);
+            Counter { value: 0, reactor }
+        }
+
+        fn reduce(&mut self, message: i32) {
+            self.value += message;
+
+            // Messages sent while reducing are handled afterwards.
+            if message == 1 {
+                self.reactor.send(100);
+            }
+        }
+    }
+
+    fn app(state: &Counter) -> impl Block<Message = i32> {
+        Build::new().block((
+            format!("Value: {}", state.value),
+            Build::with(Default::default(), Events::new().click(|_| 1)).block("Add"),
+        ))
+    }
+
+    fn click() -> Event {
+        Event::Click(Coordinates { x: 0, y: 0 })
+    }
+
+    #[test]
+    fn render() {
+        let mut harness = Harness::new(app);
+        assert_eq!("Value: 10Add", harness.render().text());
+
+        assert!(harness.event(&[1], click()));
+        assert_eq!("Value: 111Add", harness.render().text());
+
+        assert!(!harness.event(&[2], click()));
+    }
+
+    #[test]
+    fn replay() {
+        let mut harness = Harness::new(|state: &Recording<Counter>| app(state));
+
+        harness.event(&[1], click());
+        harness.send(-5);
+
+        let json = harness.state().session().to_json().unwrap();
+        let session = Session::from_json(&json).unwrap();
+        let mut replayed = Harness::replay(&session, app);
+
+        assert_eq!(harness.render().text(), replayed.render().text());
+        assert_eq!(106, replayed.state().value);
+
+        // The replayed app can still send messages through its reactor.
+        replayed.event(&[1], click());
+        assert_eq!(207, replayed.state().value);
+    }
+
+    struct Log(Vec<u32>);
+
+    impl State for Log {
+        type Message = u32;
+
+        fn new(_: Reactor<u32>) -> Self {
+            Log(Vec::new())
+        }
+
+        fn reduce(&mut self, message: u32) {
+            self.0.push(message);
+        }
+    }
+
+    #[test]
+    fn many() {
+        let mut harness = Harness::new(|_: &Log| {
+            let many = Events::new().click_many(|_| vec![1u32, 2, 3]);
+            let none = Events::new().click_filter(|_| None::<u32>);
+
+            Build::new().block((
+                Build::with(Default::default(), many).block(()),
+                Build::with(Default::default(), none).block(()),
+            ))
+        });
+
+        harness.event(&[0], click());
+        harness.event(&[1], click());
+
+        assert_eq!(vec![1, 2, 3], harness.state().0);
+    }
+
+    #[test]
+    fn text_layout() {
+        let node: Node<()> = super::render(Build::new().block((
+            Build::styled(Style::new(|style| style.text.truncate())).block("A long label"),
+            Build::styled(Style::new(|style| style.text.clamp(2))).block("A long description"),
+        )));
+
+        let label = node.get(&[0]).and_then(Node::style).unwrap();
+        assert_eq!(Wrap::None, label.text.wrap);
+        assert_eq!(Overflow::Ellipsis, label.text.overflow);
+        assert_eq!(None, label.text.lines);
+
+        let description = node.get(&[1]).and_then(Node::style).unwrap();
+        assert_eq!(Some(2), description.text.lines);
+    }
+}
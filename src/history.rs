@@ -0,0 +1,305 @@
+//! Time-travel debugging.
+//!
+//! Wrapping an app state in a `History` records every message passed to
+//! `State::reduce` along with a snapshot of the state after it was handled.
+//! The recorded states can then be stepped through with `Travel` messages,
+//! which re-render the app like any other message.
+
+use std::collections::VecDeque;
+use std::ops::Deref;
+
+use ::{State, Reactor};
+use events::Event;
+
+/// A message for an app with history.
+pub enum Travel<M> {
+    /// A message for the wrapped state.
+    Message(M),
+    /// Step back to the previous state.
+    Undo,
+    /// Step forward to the next state.
+    Redo,
+    /// Jump to the state at an index, where `0` is the oldest kept state.
+    Jump(usize),
+    /// Keep at most this many states, dropping the oldest ones.
+    Capacity(usize),
+}
+
+impl<M> From<M> for Travel<M> {
+    fn from(message: M) -> Self {
+        Travel::Message(message)
+    }
+}
+
+struct Entry<S> where S: State {
+    message: Option<S::Message>,
+    state: S,
+}
+
+/// An app state with recorded history.
+///
+/// Dereferencing gives the present state, which is whichever state undo,
+/// redo or jump last moved to.
+pub struct History<S> where S: State {
+    entries: VecDeque<Entry<S>>,
+    index: usize,
+    capacity: usize,
+}
+
+/// The number of states kept by default.
+pub const CAPACITY: usize = 100;
+
+impl<S> History<S> where S: State + Clone, S::Message: Clone {
+    /// Start a history with `state` as its only entry, at index 0.
+    pub fn wrap(state: S) -> Self {
+        let mut entries = VecDeque::new();
+        entries.push_back(Entry { message: None, state });
+
+        History {
+            entries,
+            index: 0,
+            capacity: CAPACITY,
+        }
+    }
+
+    /// Keep at most `capacity` states, of which there is always at least one.
+    ///
+    /// Once there are more, the oldest states are dropped and the indices of
+    /// the others shift down to match. The current state is always kept, so
+    /// when it is among the oldest, the newest states are dropped instead.
+    pub fn capacity(&mut self, capacity: usize) {
+        self.capacity = capacity.max(1);
+        self.trim();
+    }
+
+    /// The current state.
+    pub fn present(&self) -> &S {
+        &self.entries[self.index].state
+    }
+
+    /// The index of the current state.
+    pub fn index(&self) -> usize {
+        self.index
+    }
+
+    /// The number of kept states, including the oldest one.
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// The recorded messages, in the order they were reduced.
+    ///
+    /// The message at index `i` produced the state at index `i + 1`, so the
+    /// message that produced the oldest kept state is dropped with the state
+    /// before it.
+    pub fn messages<'a>(&'a self) -> impl Iterator<Item = &'a S::Message> + 'a {
+        self.entries.iter().filter_map(|entry| entry.message.as_ref())
+    }
+
+    pub fn can_undo(&self) -> bool {
+        self.index > 0
+    }
+
+    pub fn can_redo(&self) -> bool {
+        self.index + 1 < self.entries.len()
+    }
+
+    /// Step back to the previous state. Returns `false` if there is none.
+    pub fn undo(&mut self) -> bool {
+        let can_undo = self.can_undo();
+
+        if can_undo {
+            self.index -= 1;
+        }
+
+        can_undo
+    }
+
+    /// Step forward to the next state. Returns `false` if there is none.
+    pub fn redo(&mut self) -> bool {
+        let can_redo = self.can_redo();
+
+        if can_redo {
+            self.index += 1;
+        }
+
+        can_redo
+    }
+
+    /// Jump to the state at an index. Returns `false` if it is out of range.
+    pub fn jump(&mut self, index: usize) -> bool {
+        let in_range = index < self.entries.len();
+
+        if in_range {
+            self.index = index;
+        }
+
+        in_range
+    }
+
+    /// Reduce a message against the current state and record the result.
+    ///
+    /// Any states after the current one are discarded, as with a regular undo
+    /// stack.
+    pub fn record(&mut self, message: S::Message) {
+        let mut state = self.present().clone();
+        state.reduce(message.clone());
+
+        self.entries.truncate(self.index + 1);
+        self.entries.push_back(Entry { message: Some(message), state });
+        self.index += 1;
+
+        self.trim();
+    }
+
+    /// Drop the states beyond the capacity, keeping the current one.
+    fn trim(&mut self) {
+        let excess = self.entries.len().saturating_sub(self.capacity);
+        let oldest = excess.min(self.index);
+
+        if oldest > 0 {
+            self.entries.drain(..oldest);
+            self.index -= oldest;
+
+            if let Some(entry) = self.entries.front_mut() {
+                entry.message = None;
+            }
+        }
+
+        self.entries.truncate(self.capacity);
+    }
+}
+
+impl<S> Deref for History<S> where S: State + Clone, S::Message: Clone {
+    type Target = S;
+
+    fn deref(&self) -> &S {
+        self.present()
+    }
+}
+
+impl<S> State for History<S> where S: State + Clone, S::Message: Clone {
+    type Message = Travel<S::Message>;
+
+    fn new(reactor: Reactor<Self::Message>) -> Self {
+        History::wrap(S::new(reactor.downgrade()))
+    }
+
+    fn reduce(&mut self, message: Self::Message) {
+        match message {
+            Travel::Message(message) => self.record(message),
+            Travel::Undo => { self.undo(); }
+            Travel::Redo => { self.redo(); }
+            Travel::Jump(index) => { self.jump(index); }
+            Travel::Capacity(capacity) => self.capacity(capacity),
+        }
+    }
+
+    fn event(&mut self, event: &Event) {
+        let index = self.index;
+        self.entries[index].state.event(event);
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[derive(Clone)]
+    struct Counter(i32);
+
+    impl State for Counter {
+        type Message = i32;
+
+        fn new(_: Reactor<i32>) -> Self {
+            Counter(0)
+        }
+
+        fn reduce(&mut self, message: i32) {
+            self.0 += message;
+        }
+    }
+
+    fn history() -> History<Counter> {
+        let mut history = History::wrap(Counter(0));
+
+        history.reduce(Travel::Message(1));
+        history.reduce(Travel::Message(2));
+        history.reduce(Travel::Message(3));
+
+        history
+    }
+
+    #[test]
+    fn record() {
+        let history = history();
+
+        assert_eq!(6, history.0);
+        assert_eq!(4, history.len());
+        assert_eq!(vec![1, 2, 3], history.messages().cloned().collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn undo_redo() {
+        let mut history = history();
+
+        history.reduce(Travel::Undo);
+        assert_eq!(3, history.0);
+
+        history.reduce(Travel::Undo);
+        history.reduce(Travel::Undo);
+        assert_eq!(0, history.0);
+        assert!(!history.undo());
+
+        history.reduce(Travel::Redo);
+        assert_eq!(1, history.0);
+    }
+
+    #[test]
+    fn jump() {
+        let mut history = history();
+
+        history.reduce(Travel::Jump(2));
+        assert_eq!(3, history.0);
+        assert!(!history.jump(4));
+        assert_eq!(2, history.index());
+    }
+
+    #[test]
+    fn branch() {
+        let mut history = history();
+
+        history.reduce(Travel::Jump(1));
+        history.reduce(Travel::Message(10));
+
+        assert_eq!(11, history.0);
+        assert!(!history.can_redo());
+        assert_eq!(vec![1, 10], history.messages().cloned().collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn capacity() {
+        let mut history = history();
+
+        history.reduce(Travel::Undo);
+        history.reduce(Travel::Undo);
+        history.reduce(Travel::Capacity(2));
+
+        // The present state is kept, so the newest state goes with the oldest.
+        assert_eq!(2, history.len());
+        assert_eq!(0, history.index());
+        assert_eq!(1, history.0);
+        assert_eq!(vec![2], history.messages().cloned().collect::<Vec<_>>());
+
+        history.reduce(Travel::Redo);
+        history.reduce(Travel::Message(4));
+        assert_eq!(2, history.len());
+        assert_eq!(1, history.index());
+        assert_eq!(7, history.0);
+        assert_eq!(vec![4], history.messages().cloned().collect::<Vec<_>>());
+
+        history.reduce(Travel::Undo);
+        assert_eq!(3, history.0);
+        assert!(!history.undo());
+    }
+}
@@ -8,7 +8,15 @@ extern crate serde_json;
 pub mod ui;
 pub mod block;
 pub mod events;
+pub mod history;
+pub mod record;
+pub mod persist;
+pub mod middleware;
+pub mod select;
+pub mod slice;
+pub mod harness;
 mod reactor;
+mod queue;
 
 #[cfg(all(target_arch = "wasm32", not(target_os = "emscripten")))]
 pub mod web;
@@ -17,11 +25,23 @@ pub use reactor::Reactor;
 pub use block::{Block, Build};
 pub use events::Events;
 
+use events::{Event, Effect};
+
 pub trait Update: Send + 'static {
     type Message: 'static + Send;
 
     fn reduce(&self, message: Self::Message);
     fn clone(&self) -> Box<Update<Message = Self::Message>>;
+
+    /// Notify the app of an incoming event, before it is handled.
+    fn event(&self, _event: Event) {}
+
+    /// Run an effect in order with messages, and re-render afterwards.
+    fn effect(&self, effect: Effect<Self::Message>) {
+        if let Some(message) = effect() {
+            self.reduce(message);
+        }
+    }
 }
 
 impl<M> Update for Box<Update<Message = M>> where M: Send + 'static {
@@ -34,6 +54,14 @@ impl<M> Update for Box<Update<Message = M>> where M: Send + 'static {
     fn clone(&self) -> Box<Update<Message = Self::Message>> {
         (**self).clone()
     }
+
+    fn event(&self, event: Event) {
+        (**self).event(event);
+    }
+
+    fn effect(&self, effect: Effect<Self::Message>) {
+        (**self).effect(effect);
+    }
 }
 
 pub trait State: Send + 'static {
@@ -41,4 +69,10 @@ pub trait State: Send + 'static {
 
     fn new(Reactor<Self::Message>) -> Self;
     fn reduce(&mut self, Self::Message);
+
+    /// Observe an incoming event before its handlers run.
+    ///
+    /// Events are delivered in order with messages. Most states have no use
+    /// for this, it exists for wrappers such as `record::Recording`.
+    fn event(&mut self, _event: &Event) {}
 }
@@ -0,0 +1,250 @@
+//! Message middleware.
+//!
+//! Middleware sits between the code that sends a message (event handlers and
+//! reactors) and `State::reduce`. Each middleware receives the message along
+//! with the rest of the pipeline, and decides what to pass on:
+//!
+//! ```ignore
+//! let pipeline = Pipeline::new()
+//!     .with(|message: Message, next: &Next<Message>| {
+//!         println!("{:?}", message);
+//!         next.send(message);
+//!     })
+//!     .with(|message: Message, next: &Next<Message>| {
+//!         // Drop messages while the app is read-only.
+//!         if !read_only() {
+//!             next.send(message);
+//!         }
+//!     });
+//!
+//! web::launch_with(pipeline, app);
+//! ```
+
+use std::sync::Arc;
+
+use ::Update;
+
+pub trait Middleware<M>: Send + Sync + 'static {
+    /// Handle a message.
+    ///
+    /// Calling `next.send` passes a message on to the next middleware, or to
+    /// the state once the end of the pipeline is reached. It may be called any
+    /// number of times, with any message, and `next` may be cloned to send
+    /// messages later.
+    fn handle(&self, message: M, next: &Next<M>);
+}
+
+impl<M, F> Middleware<M> for F where F: Send + Sync + 'static + Fn(M, &Next<M>) {
+    fn handle(&self, message: M, next: &Next<M>) {
+        self(message, next);
+    }
+}
+
+/// An ordered list of middleware.
+///
+/// Messages pass through middleware in the order it was added.
+pub struct Pipeline<M> {
+    chain: Vec<Box<Middleware<M>>>,
+}
+
+impl<M> Pipeline<M> where M: Send + 'static {
+    /// An empty pipeline, which passes every message straight through.
+    pub fn new() -> Self {
+        Pipeline { chain: Vec::new() }
+    }
+
+    /// Append a middleware to the end of the pipeline.
+    pub fn with<W>(mut self, middleware: W) -> Self where W: Middleware<M> {
+        self.chain.push(Box::new(middleware));
+        self
+    }
+
+    pub(crate) fn share(self) -> Chain<M> {
+        Chain(Arc::new(self.chain))
+    }
+}
+
+/// A shared pipeline, ready to wrap update targets.
+pub(crate) struct Chain<M>(Arc<Vec<Box<Middleware<M>>>>);
+
+impl<M> Chain<M> where M: Send + 'static {
+    /// Route messages sent to an update target through the pipeline.
+    pub fn wrap<U>(&self, update: U) -> Piped<M> where U: Update<Message = M> {
+        Piped {
+            chain: self.clone(),
+            update: Box::new(update),
+        }
+    }
+}
+
+impl<M> Clone for Chain<M> {
+    fn clone(&self) -> Self {
+        Chain(self.0.clone())
+    }
+}
+
+/// An update target behind a pipeline.
+pub(crate) struct Piped<M> {
+    chain: Chain<M>,
+    update: Box<Update<Message = M>>,
+}
+
+impl<M> Update for Piped<M> where M: Send + 'static {
+    type Message = M;
+
+    fn reduce(&self, message: M) {
+        let next = Next {
+            chain: self.chain.clone(),
+            index: 0,
+            update: self.update.clone(),
+        };
+
+        next.send(message);
+    }
+
+    fn clone(&self) -> Box<Update<Message = M>> {
+        Box::new(Piped {
+            chain: self.chain.clone(),
+            update: self.update.clone(),
+        })
+    }
+
+    fn event(&self, event: ::events::Event) {
+        self.update.event(event);
+    }
+
+    /// Effects skip the pipeline, but the message they produce does not.
+    fn effect(&self, effect: ::events::Effect<M>) {
+        let piped = self.clone();
+
+        self.update.effect(Box::new(move || {
+            if let Some(message) = effect() {
+                piped.reduce(message);
+            }
+
+            None
+        }));
+    }
+}
+
+/// The remainder of a pipeline.
+pub struct Next<M> {
+    chain: Chain<M>,
+    index: usize,
+    update: Box<Update<Message = M>>,
+}
+
+impl<M> Next<M> where M: Send + 'static {
+    /// Pass a message on to the rest of the pipeline.
+    pub fn send(&self, message: M) {
+        match (self.chain.0).get(self.index) {
+            Some(middleware) => {
+                let next = Next {
+                    chain: self.chain.clone(),
+                    index: self.index + 1,
+                    update: self.update.clone(),
+                };
+
+                middleware.handle(message, &next);
+            }
+
+            None => self.update.reduce(message),
+        }
+    }
+}
+
+impl<M> Clone for Next<M> where M: Send + 'static {
+    fn clone(&self) -> Self {
+        Next {
+            chain: self.chain.clone(),
+            index: self.index,
+            update: self.update.clone(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::sync::Mutex;
+
+    use ::{State, Reactor};
+    use harness::Harness;
+    use block::{Block, Build};
+    use super::*;
+
+    struct Log(Vec<i32>);
+
+    impl State for Log {
+        type Message = i32;
+
+        fn new(reactor: Reactor<i32>) -> Self {
+            reactor.send(1);
+            Log(Vec::new())
+        }
+
+        fn reduce(&mut self, message: i32) {
+            self.0.push(message);
+        }
+    }
+
+    fn app(_: &Log) -> impl Block<Message = i32> {
+        Build::new().block(())
+    }
+
+    #[test]
+    fn order() {
+        let pipeline = Pipeline::new()
+            .with(|m: i32, next: &Next<i32>| next.send(m * 10))
+            .with(|m: i32, next: &Next<i32>| next.send(m + 1));
+
+        let mut harness = Harness::piped(pipeline, app);
+        harness.send(2);
+
+        assert_eq!(vec![11, 21], harness.state().0);
+    }
+
+    #[test]
+    fn drop_and_split() {
+        let pipeline = Pipeline::new()
+            .with(|m: i32, next: &Next<i32>| {
+                if m % 2 == 0 {
+                    next.send(m);
+                    next.send(m);
+                }
+            });
+
+        let mut harness = Harness::piped(pipeline, app);
+        harness.send(3);
+        harness.send(4);
+
+        assert_eq!(vec![4, 4], harness.state().0);
+    }
+
+    #[test]
+    fn delay() {
+        let held = Arc::new(Mutex::new(Vec::new()));
+
+        let pipeline = {
+            let held = held.clone();
+
+            Pipeline::new()
+                .with(move |m: i32, next: &Next<i32>| {
+                    held.lock().unwrap().push((m, next.clone()));
+                })
+        };
+
+        let mut harness = Harness::piped(pipeline, app);
+        harness.send(2);
+        assert!(harness.state().0.is_empty());
+
+        // Release the held messages in reverse order.
+        let released = held.lock().unwrap().drain(..).rev().collect::<Vec<_>>();
+
+        for (m, next) in released {
+            next.send(m);
+        }
+
+        harness.send(3);
+        assert_eq!(vec![2, 1], harness.state().0);
+    }
+}
@@ -0,0 +1,495 @@
+//! State persistence.
+//!
+//! A state implementing `Persist` describes a serializable snapshot of itself.
+//! Wrapping it in a `Persisted` saves a snapshot whenever the state changes,
+//! and `restore` loads the last saved snapshot, typically from `State::new`:
+//!
+//! ```ignore
+//! fn new(reactor: Reactor<Message>) -> Self {
+//!     let todos = persist::restore::<Self>().unwrap_or_default();
+//!
+//!     State { todos, reactor }
+//! }
+//! ```
+
+use std::env;
+use std::fs;
+use std::io::{Read, Write};
+use std::ops::Deref;
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex};
+
+use serde::Serialize;
+use serde::de::DeserializeOwned;
+use serde_json::{self, Value};
+
+use ::{State, Reactor};
+use events::Event;
+
+/// A state that can be saved and restored.
+pub trait Persist: State {
+    /// The serialized form of the state.
+    type Snapshot: 'static + Send + Serialize + DeserializeOwned;
+
+    /// The key the snapshot is stored under.
+    const KEY: &'static str;
+
+    /// The schema version of `Snapshot`. Bump this whenever the format changes.
+    const VERSION: u32;
+
+    /// How long to wait for further changes before saving, in milliseconds.
+    const DEBOUNCE: u32 = 250;
+
+    fn snapshot(&self) -> Self::Snapshot;
+
+    /// Upgrade a snapshot saved with an older schema version.
+    ///
+    /// Returning `None` discards the snapshot. By default, old snapshots are
+    /// always discarded.
+    fn migrate(_version: u32, _snapshot: Value) -> Option<Self::Snapshot> {
+        None
+    }
+
+    /// The storage backend to use.
+    fn storage() -> Box<Storage> {
+        default_storage()
+    }
+}
+
+/// A key-value store for snapshots.
+pub trait Storage: Send + Sync {
+    fn load(&self, key: &str) -> Option<String>;
+    fn save(&self, key: &str, value: &str);
+}
+
+/// Stores each key in a JSON file inside a directory.
+pub struct FileStorage {
+    directory: PathBuf,
+}
+
+impl FileStorage {
+    pub fn new<P>(directory: P) -> Self where P: Into<PathBuf> {
+        FileStorage { directory: directory.into() }
+    }
+
+    fn path(&self, key: &str) -> PathBuf {
+        self.directory.join(format!("{}.json", key))
+    }
+}
+
+impl Default for FileStorage {
+    /// Store files in a `blocks` folder in the temporary directory.
+    fn default() -> Self {
+        FileStorage::new(env::temp_dir().join("blocks"))
+    }
+}
+
+impl Storage for FileStorage {
+    fn load(&self, key: &str) -> Option<String> {
+        let mut contents = String::new();
+
+        fs::File::open(self.path(key))
+            .and_then(|mut file| file.read_to_string(&mut contents))
+            .ok()
+            .map(|_| contents)
+    }
+
+    fn save(&self, key: &str, value: &str) {
+        // Persistence is best effort, a failed save must not crash the app.
+        let _ = fs::create_dir_all(&self.directory)
+            .and_then(|_| fs::File::create(self.path(key)))
+            .and_then(|mut file| file.write_all(value.as_bytes()));
+    }
+}
+
+#[cfg(all(target_arch = "wasm32", not(target_os = "emscripten")))]
+fn default_storage() -> Box<Storage> {
+    Box::new(::web::LocalStorage)
+}
+
+#[cfg(not(all(target_arch = "wasm32", not(target_os = "emscripten"))))]
+fn default_storage() -> Box<Storage> {
+    Box::new(FileStorage::default())
+}
+
+/// Calls `tick` every `delay` milliseconds until it returns `true`.
+trait Timer: Send + Sync {
+    fn repeat(&self, delay: u32, tick: Box<FnMut() -> bool + Send>);
+}
+
+/// The timer of the platform.
+struct Clock;
+
+/// Ticks with browser timeouts.
+#[cfg(all(target_arch = "wasm32", not(target_os = "emscripten")))]
+impl Timer for Clock {
+    fn repeat(&self, delay: u32, mut tick: Box<FnMut() -> bool + Send>) {
+        ::web::ffi::timeout(delay, move || {
+            if !tick() {
+                Clock.repeat(delay, tick);
+            }
+        });
+    }
+}
+
+/// Ticks on a thread of its own, which ends after the last tick.
+#[cfg(not(all(target_arch = "wasm32", not(target_os = "emscripten"))))]
+impl Timer for Clock {
+    fn repeat(&self, delay: u32, mut tick: Box<FnMut() -> bool + Send>) {
+        use std::thread;
+        use std::time::Duration;
+
+        thread::spawn(move || loop {
+            thread::sleep(Duration::from_millis(delay as u64));
+
+            if tick() {
+                break;
+            }
+        });
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+struct Versioned<T> {
+    version: u32,
+    state: T,
+}
+
+/// Load the last saved snapshot of a state from its storage.
+///
+/// Snapshots from older schema versions are passed through `Persist::migrate`.
+/// Snapshots that are missing, unreadable or from a newer version are
+/// discarded.
+pub fn restore<S>() -> Option<S::Snapshot> where S: Persist {
+    restore_from::<S>(&*S::storage())
+}
+
+/// Load the last saved snapshot of a state from a storage backend.
+pub fn restore_from<S>(storage: &Storage) -> Option<S::Snapshot> where S: Persist {
+    let json = storage.load(S::KEY)?;
+    let Versioned { version, state } = serde_json::from_str::<Versioned<Value>>(&json).ok()?;
+
+    if version == S::VERSION {
+        serde_json::from_value(state).ok()
+    } else if version < S::VERSION {
+        S::migrate(version, state)
+    } else {
+        None
+    }
+}
+
+/// Save a snapshot of a state to a storage backend immediately.
+pub fn save_to<S>(state: &S, storage: &Storage) where S: Persist {
+    write::<S>(state.snapshot(), storage);
+}
+
+fn write<S>(state: S::Snapshot, storage: &Storage) where S: Persist {
+    let versioned = Versioned {
+        version: S::VERSION,
+        state,
+    };
+
+    if let Ok(json) = serde_json::to_string(&versioned) {
+        storage.save(S::KEY, &json);
+    }
+}
+
+struct Pending<T> {
+    /// The number of changes so far.
+    changes: u64,
+    /// The snapshot of the last change, if it has not been saved yet.
+    snapshot: Option<T>,
+    /// The number of changes at the last tick of the timer, or `None` if the
+    /// timer is not running.
+    tick: Option<u64>,
+}
+
+/// Writes the latest snapshot once changes have settled.
+///
+/// A single timer runs while there is an unsaved snapshot. Each tick saves it
+/// if nothing changed since the previous tick, and otherwise waits for the
+/// next one.
+struct Saver<T> {
+    storage: Arc<Storage>,
+    timer: Box<Timer>,
+    delay: u32,
+    pending: Arc<Mutex<Pending<T>>>,
+}
+
+impl<T> Saver<T> where T: 'static + Send + Serialize {
+    fn new(storage: Arc<Storage>, timer: Box<Timer>, delay: u32) -> Self {
+        Saver {
+            storage,
+            timer,
+            delay,
+            pending: Arc::new(Mutex::new(Pending {
+                changes: 0,
+                snapshot: None,
+                tick: None,
+            })),
+        }
+    }
+
+    /// Save `snapshot` once a whole delay passed without further changes.
+    fn schedule<S>(&self, snapshot: T) where S: Persist<Snapshot = T> {
+        let start = {
+            let mut pending = self.pending.lock().unwrap();
+            pending.changes += 1;
+            pending.snapshot = Some(snapshot);
+
+            let start = pending.tick.is_none();
+
+            if start {
+                pending.tick = Some(pending.changes);
+            }
+
+            start
+        };
+
+        if start {
+            let storage = self.storage.clone();
+            let pending = self.pending.clone();
+
+            self.timer.repeat(self.delay, Box::new(move || {
+                let snapshot = {
+                    let mut pending = pending.lock().unwrap();
+
+                    if pending.tick != Some(pending.changes) {
+                        pending.tick = Some(pending.changes);
+                        return false;
+                    }
+
+                    pending.tick = None;
+                    pending.snapshot.take()
+                };
+
+                if let Some(snapshot) = snapshot {
+                    write::<S>(snapshot, &*storage);
+                }
+
+                true
+            }));
+        }
+    }
+}
+
+/// An app state that is saved whenever it changes.
+///
+/// A snapshot is taken after every message, but it is only serialized and
+/// written to storage once a full period of `Persist::DEBOUNCE` milliseconds
+/// passed without messages. Views see the wrapped state through `Deref`.
+pub struct Persisted<S> where S: Persist {
+    state: S,
+    saver: Saver<S::Snapshot>,
+}
+
+impl<S> Persisted<S> where S: Persist {
+    pub fn wrap(state: S) -> Self {
+        Persisted {
+            state,
+            saver: Saver::new(Arc::from(S::storage()), Box::new(Clock), S::DEBOUNCE),
+        }
+    }
+}
+
+impl<S> Deref for Persisted<S> where S: Persist {
+    type Target = S;
+
+    fn deref(&self) -> &S {
+        &self.state
+    }
+}
+
+impl<S> State for Persisted<S> where S: Persist {
+    type Message = S::Message;
+
+    fn new(reactor: Reactor<Self::Message>) -> Self {
+        Persisted::wrap(S::new(reactor))
+    }
+
+    fn reduce(&mut self, message: Self::Message) {
+        self.state.reduce(message);
+        self.saver.schedule::<S>(self.state.snapshot());
+    }
+
+    fn event(&mut self, event: &Event) {
+        self.state.event(event);
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::collections::HashMap;
+
+    use super::*;
+
+    #[derive(Default)]
+    struct Memory(Mutex<HashMap<String, String>>);
+
+    impl Storage for Memory {
+        fn load(&self, key: &str) -> Option<String> {
+            self.0.lock().unwrap().get(key).cloned()
+        }
+
+        fn save(&self, key: &str, value: &str) {
+            self.0.lock().unwrap().insert(key.to_string(), value.to_string());
+        }
+    }
+
+    struct Counter(i32);
+
+    impl State for Counter {
+        type Message = i32;
+
+        fn new(_: Reactor<i32>) -> Self {
+            Counter(0)
+        }
+
+        fn reduce(&mut self, message: i32) {
+            self.0 += message;
+        }
+    }
+
+    impl Persist for Counter {
+        type Snapshot = i32;
+
+        const KEY: &'static str = "counter";
+        const VERSION: u32 = 2;
+
+        fn snapshot(&self) -> i32 {
+            self.0
+        }
+
+        fn migrate(version: u32, snapshot: Value) -> Option<i32> {
+            // Version 1 stored the value as a string.
+            match (version, snapshot) {
+                (1, Value::String(s)) => s.parse().ok(),
+                _ => None,
+            }
+        }
+    }
+
+    #[test]
+    fn round_trip() {
+        let storage = Memory::default();
+
+        assert_eq!(None, restore_from::<Counter>(&storage));
+
+        save_to(&Counter(5), &storage);
+        assert_eq!(Some(5), restore_from::<Counter>(&storage));
+    }
+
+    #[test]
+    fn versions() {
+        let storage = Memory::default();
+
+        storage.save("counter", r#"{"version":1,"state":"7"}"#);
+        assert_eq!(Some(7), restore_from::<Counter>(&storage));
+
+        storage.save("counter", r#"{"version":0,"state":"7"}"#);
+        assert_eq!(None, restore_from::<Counter>(&storage));
+
+        storage.save("counter", r#"{"version":3,"state":7}"#);
+        assert_eq!(None, restore_from::<Counter>(&storage));
+
+        storage.save("counter", "garbage");
+        assert_eq!(None, restore_from::<Counter>(&storage));
+    }
+
+    lazy_static! {
+        static ref SAVED: Memory = Memory::default();
+    }
+
+    struct Global;
+
+    impl Storage for Global {
+        fn load(&self, key: &str) -> Option<String> {
+            SAVED.load(key)
+        }
+
+        fn save(&self, key: &str, value: &str) {
+            SAVED.save(key, value);
+        }
+    }
+
+    /// A counter that restores itself from the global storage.
+    struct Saved(i32);
+
+    impl State for Saved {
+        type Message = i32;
+
+        fn new(_: Reactor<i32>) -> Self {
+            Saved(restore::<Saved>().unwrap_or(0))
+        }
+
+        fn reduce(&mut self, message: i32) {
+            self.0 += message;
+        }
+    }
+
+    impl Persist for Saved {
+        type Snapshot = i32;
+
+        const KEY: &'static str = "saved";
+        const VERSION: u32 = 1;
+        const DEBOUNCE: u32 = 100;
+
+        fn snapshot(&self) -> i32 {
+            self.0
+        }
+
+        fn storage() -> Box<Storage> {
+            Box::new(Global)
+        }
+    }
+
+    /// A timer that only ticks when told to.
+    #[derive(Clone, Default)]
+    struct Manual(Arc<Mutex<Vec<Box<FnMut() -> bool + Send>>>>);
+
+    impl Manual {
+        /// Tick every running timer, returning how many are still running.
+        fn tick(&self) -> usize {
+            let mut ticks = self.0.lock().unwrap();
+            ticks.retain_mut(|tick| !tick());
+            ticks.len()
+        }
+    }
+
+    impl Timer for Manual {
+        fn repeat(&self, _: u32, tick: Box<FnMut() -> bool + Send>) {
+            self.0.lock().unwrap().push(tick);
+        }
+    }
+
+    #[test]
+    fn persisted() {
+        use record::Inert;
+
+        save_to(&Saved(7), &Global);
+
+        let mut persisted = Persisted::<Saved>::new(Reactor::new(Inert::new()));
+        assert_eq!(7, persisted.0);
+
+        let timer = Manual::default();
+        persisted.saver = Saver::new(Arc::new(Global), Box::new(timer.clone()), Saved::DEBOUNCE);
+
+        // A change between ticks delays the save, with a single timer for
+        // every change.
+        persisted.reduce(1);
+        persisted.reduce(1);
+        assert_eq!(1, timer.0.lock().unwrap().len());
+        assert_eq!(Some(7), restore::<Saved>());
+
+        assert_eq!(1, timer.tick());
+        assert_eq!(Some(7), restore::<Saved>());
+
+        assert_eq!(0, timer.tick());
+        assert_eq!(Some(9), restore::<Saved>());
+
+        // The timer starts again with the next change.
+        persisted.reduce(1);
+        assert_eq!(0, timer.tick());
+        assert_eq!(Some(10), restore::<Saved>());
+    }
+}
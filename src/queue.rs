@@ -0,0 +1,144 @@
+use std::collections::VecDeque;
+use std::sync::{Mutex, MutexGuard};
+use std::sync::atomic::{AtomicBool, Ordering};
+
+use events::{Event, Effect};
+
+/// A unit of work for an app runtime.
+pub enum Job<M> {
+    /// An incoming event, delivered to `State::event`.
+    Event(Event),
+    /// A message, delivered to `State::reduce`.
+    Message(M),
+    /// An effect from a component, followed by a render.
+    Effect(Effect<M>),
+    /// A render without anything to reduce first.
+    Render,
+}
+
+/// A FIFO message queue that is safe to push to while it is being drained.
+///
+/// Messages sent while a drain is in progress are appended to the back of the
+/// queue and processed by the active drain once all earlier messages have
+/// been handled, rather than re-entering the consumer.
+pub struct Queue<M> {
+    pending: Mutex<VecDeque<M>>,
+    draining: AtomicBool,
+}
+
+/// Resets the draining flag, even if the consumer panics.
+struct Draining<'a>(&'a AtomicBool);
+
+impl<'a> Drop for Draining<'a> {
+    fn drop(&mut self) {
+        self.0.store(false, Ordering::SeqCst);
+    }
+}
+
+impl<M> Queue<M> {
+    pub fn new() -> Self {
+        Queue {
+            pending: Mutex::new(VecDeque::new()),
+            draining: AtomicBool::new(false),
+        }
+    }
+
+    /// Append a message to the back of the queue.
+    pub fn push(&self, message: M) {
+        self.pending().push_back(message);
+    }
+
+    /// Whether there is nothing left to process.
+    pub fn is_empty(&self) -> bool {
+        self.pending().is_empty()
+    }
+
+    /// Process every queued message in order.
+    ///
+    /// If the queue is already being drained further up the stack, this
+    /// returns immediately and the active drain picks up any new messages.
+    pub fn drain<F>(&self, mut f: F) where F: FnMut(M) {
+        loop {
+            if self.draining.swap(true, Ordering::SeqCst) {
+                return;
+            }
+
+            {
+                let _draining = Draining(&self.draining);
+
+                while let Some(message) = self.pop() {
+                    f(message);
+                }
+            }
+
+            // Another thread may have pushed after the last pop, but before
+            // the flag was cleared, without starting a drain of its own.
+            if self.is_empty() {
+                return;
+            }
+        }
+    }
+
+    /// Take the message at the front of the queue.
+    ///
+    /// The lock must not be held while a message is processed, since that
+    /// may push.
+    fn pop(&self) -> Option<M> {
+        self.pending().pop_front()
+    }
+
+    fn pending<'a>(&'a self) -> MutexGuard<'a, VecDeque<M>> {
+        self.pending
+            .lock()
+            .expect("blocks: the message queue was poisoned by a panic during dispatch")
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::cell::RefCell;
+
+    use super::*;
+
+    #[test]
+    fn fifo() {
+        let queue = Queue::new();
+        let mut seen = Vec::new();
+
+        queue.push(1);
+        queue.push(2);
+        queue.push(3);
+        queue.drain(|m| seen.push(m));
+
+        assert_eq!(vec![1, 2, 3], seen);
+    }
+
+    #[test]
+    fn reentrant() {
+        let queue = Queue::new();
+        let seen = RefCell::new(Vec::new());
+
+        queue.push(1);
+        queue.drain(|m| {
+            seen.borrow_mut().push(m);
+
+            if m < 3 {
+                queue.push(m * 10);
+                queue.push(m + 1);
+
+                // A nested drain must not process anything itself.
+                queue.drain(|_| panic!("nested drain"));
+            }
+        });
+
+        assert_eq!(vec![1, 10, 2, 20, 3], seen.into_inner());
+
+        // The queue can be drained again afterwards.
+        let mut seen = Vec::new();
+
+        queue.push(4);
+        queue.drain(|m| seen.push(m));
+
+        assert_eq!(vec![4], seen);
+    }
+}
@@ -44,6 +44,15 @@ impl<M> Reactor<M> where M: Send + 'static {
         Self { update: Box::new(update) }
     }
 
+    /// Send a message to the app.
+    ///
+    /// This is safe to call at any time, including from `State::new` and
+    /// `State::reduce`. Messages sent while another message is being handled
+    /// are queued and processed in the order they were sent.
+    pub fn send(&self, message: M) {
+        self.update.reduce(message);
+    }
+
     pub fn downgrade<T>(&self) -> Reactor<T>
     where
         M: From<T>,
@@ -54,3 +63,9 @@ impl<M> Reactor<M> where M: Send + 'static {
         }
     }
 }
+
+impl<M> Clone for Reactor<M> where M: Send + 'static {
+    fn clone(&self) -> Self {
+        Reactor { update: self.update.clone() }
+    }
+}
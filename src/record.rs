@@ -0,0 +1,292 @@
+//! Session recording and deterministic replay.
+//!
+//! Wrapping an app state in a `Recording` logs every incoming event and every
+//! message with a timestamp. The resulting `Session` serializes to JSON and can
+//! be replayed into a fresh state, either in the browser with `web::replay` or
+//! natively with `harness::Harness::replay`.
+
+use std::ops::Deref;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+use serde::Serialize;
+use serde::de::DeserializeOwned;
+use serde_json;
+
+use ::{State, Reactor, Update};
+use events::Event;
+
+/// A single recorded entry. Times are in milliseconds since recording started.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum Entry<M> {
+    Event {
+        time: f64,
+        event: Event,
+    },
+    Message {
+        time: f64,
+        message: M,
+    },
+}
+
+impl<M> Entry<M> {
+    pub fn time(&self) -> f64 {
+        match *self {
+            Entry::Event { time, .. } | Entry::Message { time, .. } => time,
+        }
+    }
+}
+
+/// A recorded stream of events and messages.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Session<M> {
+    entries: Vec<Entry<M>>,
+}
+
+impl<M> Session<M> {
+    pub fn new() -> Self {
+        Session { entries: Vec::new() }
+    }
+
+    pub fn entries(&self) -> &[Entry<M>] {
+        &self.entries
+    }
+
+    pub fn push(&mut self, entry: Entry<M>) {
+        self.entries.push(entry);
+    }
+
+    pub fn to_json(&self) -> serde_json::Result<String> where M: Serialize {
+        serde_json::to_string(self)
+    }
+
+    pub fn from_json(json: &str) -> serde_json::Result<Self> where M: DeserializeOwned {
+        serde_json::from_str(json)
+    }
+
+    /// Feed every recorded entry into a state, in order.
+    pub fn apply<S>(&self, state: &mut S) where S: State<Message = M>, M: Clone {
+        for entry in &self.entries {
+            match *entry {
+                Entry::Event { ref event, .. } => state.event(event),
+                Entry::Message { ref message, .. } => state.reduce(message.clone()),
+            }
+        }
+    }
+
+    /// Reproduce the recorded state from scratch, with `reactor` as its
+    /// reactor.
+    ///
+    /// Messages sent while the session is being applied are dropped, since
+    /// they were recorded too and are replayed from the session. Messages
+    /// sent afterwards go to `reactor` as usual.
+    pub fn replay<S>(&self, reactor: Reactor<M>) -> S
+    where
+        S: State<Message = M>,
+        M: Clone + Send + 'static,
+    {
+        let muted = Arc::new(AtomicBool::new(true));
+        let mut state = S::new(Reactor::new(Muted { reactor, muted: muted.clone() }));
+
+        self.apply(&mut state);
+        muted.store(false, Ordering::SeqCst);
+
+        state
+    }
+}
+
+/// An app state that records a session.
+///
+/// Recording does not change what is rendered, and views of the wrapped
+/// state can be passed the recording through `Deref`.
+pub struct Recording<S> where S: State {
+    state: S,
+    session: Session<S::Message>,
+    start: f64,
+}
+
+impl<S> Recording<S> where S: State, S::Message: Clone {
+    /// Record everything that happens to `state` from now on, with times
+    /// relative to this call.
+    pub fn wrap(state: S) -> Self {
+        Recording {
+            state,
+            session: Session::new(),
+            start: now(),
+        }
+    }
+
+    /// The session recorded so far.
+    pub fn session(&self) -> &Session<S::Message> {
+        &self.session
+    }
+
+    fn elapsed(&self) -> f64 {
+        now() - self.start
+    }
+}
+
+impl<S> Deref for Recording<S> where S: State {
+    type Target = S;
+
+    fn deref(&self) -> &S {
+        &self.state
+    }
+}
+
+impl<S> State for Recording<S> where S: State, S::Message: Clone {
+    type Message = S::Message;
+
+    fn new(reactor: Reactor<Self::Message>) -> Self {
+        Recording::wrap(S::new(reactor))
+    }
+
+    fn reduce(&mut self, message: Self::Message) {
+        let time = self.elapsed();
+
+        self.session.push(Entry::Message { time, message: message.clone() });
+        self.state.reduce(message);
+    }
+
+    fn event(&mut self, event: &Event) {
+        let time = self.elapsed();
+
+        self.session.push(Entry::Event { time, event: *event });
+        self.state.event(event);
+    }
+}
+
+/// A reactor that drops messages while a session is being replayed.
+struct Muted<M> {
+    reactor: Reactor<M>,
+    muted: Arc<AtomicBool>,
+}
+
+impl<M> Update for Muted<M> where M: Send + 'static {
+    type Message = M;
+
+    fn reduce(&self, message: M) {
+        if !self.muted.load(Ordering::SeqCst) {
+            self.reactor.send(message);
+        }
+    }
+
+    fn clone(&self) -> Box<Update<Message = M>> {
+        Box::new(Muted {
+            reactor: self.reactor.clone(),
+            muted: self.muted.clone(),
+        })
+    }
+}
+
+/// An update target that drops every message.
+pub(crate) struct Inert<M>(::std::marker::PhantomData<M>);
+
+impl<M> Inert<M> {
+    pub(crate) fn new() -> Self {
+        Inert(::std::marker::PhantomData)
+    }
+}
+
+impl<M> Update for Inert<M> where M: Send + 'static {
+    type Message = M;
+
+    fn reduce(&self, _: M) {}
+
+    fn clone(&self) -> Box<Update<Message = M>> {
+        Box::new(Inert::new())
+    }
+}
+
+/// Milliseconds from an arbitrary, fixed point in time.
+#[cfg(all(target_arch = "wasm32", not(target_os = "emscripten")))]
+fn now() -> f64 {
+    ::web::ffi::now()
+}
+
+/// Milliseconds from an arbitrary, fixed point in time.
+#[cfg(not(all(target_arch = "wasm32", not(target_os = "emscripten"))))]
+fn now() -> f64 {
+    use std::time::Instant;
+
+    lazy_static! {
+        static ref EPOCH: Instant = Instant::now();
+    }
+
+    let elapsed = EPOCH.elapsed();
+
+    elapsed.as_secs() as f64 * 1000.0 + elapsed.subsec_nanos() as f64 / 1_000_000.0
+}
+
+#[cfg(test)]
+mod test {
+    use events::{Coordinates, Button};
+    use super::*;
+
+    #[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+    enum Message {
+        Add(i32),
+        Reset,
+    }
+
+    struct Counter {
+        value: i32,
+        clicks: u32,
+    }
+
+    impl State for Counter {
+        type Message = Message;
+
+        fn new(_: Reactor<Message>) -> Self {
+            Counter { value: 0, clicks: 0 }
+        }
+
+        fn reduce(&mut self, message: Message) {
+            match message {
+                Message::Add(n) => self.value += n,
+                Message::Reset => self.value = 0,
+            }
+        }
+
+        fn event(&mut self, _: &Event) {
+            self.clicks += 1;
+        }
+    }
+
+    fn record() -> Recording<Counter> {
+        let mut recording = Recording::<Counter>::new(Reactor::new(Inert::new()));
+
+        recording.event(&Event::Click(Coordinates { x: 1, y: 2 }));
+        recording.reduce(Message::Add(5));
+        recording.reduce(Message::Reset);
+        recording.event(&Event::MouseDown(Coordinates { x: 3, y: 4 }, Button::Right));
+        recording.reduce(Message::Add(2));
+
+        recording
+    }
+
+    #[test]
+    fn entries() {
+        let recording = record();
+        let entries = recording.session().entries();
+
+        assert_eq!(5, entries.len());
+        assert!(entries.windows(2).all(|w| w[0].time() <= w[1].time()));
+
+        match entries[1] {
+            Entry::Message { message, .. } => assert_eq!(Message::Add(5), message),
+            _ => panic!("expected a message"),
+        }
+    }
+
+    #[test]
+    fn replay() {
+        let recording = record();
+        let json = recording.session().to_json().unwrap();
+        let session = Session::<Message>::from_json(&json).unwrap();
+        let replayed: Counter = session.replay(Reactor::new(Inert::new()));
+
+        assert_eq!(recording.value, replayed.value);
+        assert_eq!(recording.clicks, replayed.clicks);
+    }
+}
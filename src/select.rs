@@ -0,0 +1,136 @@
+//! Derived state that is only recomputed when the state it depends on
+//! changes.
+//!
+//! A selector is kept as a field of the app state. It picks its inputs out of
+//! the state, and caches the value computed from them until they change:
+//!
+//! ```ignore
+//! struct App {
+//!     todos: Vec<Todo>,
+//!     filter: Filter,
+//!     visible: Selector<App, (Vec<Todo>, Filter), Vec<Todo>>,
+//! }
+//!
+//! impl State for App {
+//!     fn new(_: Reactor<Message>) -> Self {
+//!         App {
+//!             todos: Vec::new(),
+//!             filter: Filter::All,
+//!             visible: Selector::new(
+//!                 |app: &App| (app.todos.clone(), app.filter),
+//!                 |&(ref todos, filter)| filter.apply(todos),
+//!             ),
+//!         }
+//!     }
+//!     ...
+//! }
+//!
+//! fn view(state: &App) -> impl Block<Message = Message> {
+//!     let visible = state.visible.get(state);
+//!     ...
+//! }
+//! ```
+//!
+//! Inputs are compared on every access, so they should be cheap to clone and
+//! compare. Wrapping large parts of the state in an `Arc` helps, and selectors
+//! can use other selectors as their inputs.
+
+use std::sync::{Arc, Mutex};
+
+/// A value derived from a state of type `S`, computed from inputs of type `K`.
+pub struct Selector<S, K, T> {
+    input: Arc<Fn(&S) -> K + Send + Sync>,
+    compute: Arc<Fn(&K) -> T + Send + Sync>,
+    cache: Mutex<Option<(K, Arc<T>)>>,
+}
+
+impl<S, K, T> Selector<S, K, T> where K: PartialEq {
+    /// Create a selector from a function picking its inputs out of the state,
+    /// and a function computing the derived value from them.
+    pub fn new<I, C>(input: I, compute: C) -> Self
+    where
+        I: 'static + Send + Sync + Fn(&S) -> K,
+        C: 'static + Send + Sync + Fn(&K) -> T,
+    {
+        Selector {
+            input: Arc::new(input),
+            compute: Arc::new(compute),
+            cache: Mutex::new(None),
+        }
+    }
+
+    /// The derived value for a state, computed only if its inputs changed
+    /// since the last call.
+    pub fn get(&self, state: &S) -> Arc<T> {
+        let inputs = (self.input)(state);
+        let mut cache = self.cache.lock().unwrap();
+
+        if let Some((ref cached, ref value)) = *cache {
+            if *cached == inputs {
+                return value.clone();
+            }
+        }
+
+        let value = Arc::new((self.compute)(&inputs));
+        *cache = Some((inputs, value.clone()));
+
+        value
+    }
+
+    /// Drop the cached value, so that it is recomputed on the next call.
+    pub fn clear(&self) {
+        *self.cache.lock().unwrap() = None;
+    }
+}
+
+impl<S, K, T> Clone for Selector<S, K, T> where K: Clone {
+    fn clone(&self) -> Self {
+        Selector {
+            input: self.input.clone(),
+            compute: self.compute.clone(),
+            cache: Mutex::new(self.cache.lock().unwrap().clone()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    use super::*;
+
+    static COMPUTED: AtomicUsize = AtomicUsize::new(0);
+
+    struct Numbers {
+        values: Vec<u32>,
+        minimum: u32,
+    }
+
+    fn large() -> Selector<Numbers, (Vec<u32>, u32), Vec<u32>> {
+        Selector::new(
+            |numbers: &Numbers| (numbers.values.clone(), numbers.minimum),
+            |&(ref values, minimum)| {
+                COMPUTED.fetch_add(1, Ordering::SeqCst);
+                values.iter().cloned().filter(|&value| value >= minimum).collect()
+            },
+        )
+    }
+
+    #[test]
+    fn cache() {
+        let selector = large();
+        let mut numbers = Numbers { values: vec![1, 5, 10], minimum: 5 };
+
+        assert_eq!(vec![5, 10], *selector.get(&numbers));
+        assert_eq!(vec![5, 10], *selector.get(&numbers));
+        assert_eq!(1, COMPUTED.load(Ordering::SeqCst));
+
+        numbers.minimum = 10;
+        assert_eq!(vec![10], *selector.get(&numbers));
+        assert_eq!(2, COMPUTED.load(Ordering::SeqCst));
+
+        selector.clear();
+        selector.get(&numbers);
+        assert_eq!(3, COMPUTED.load(Ordering::SeqCst));
+    }
+}
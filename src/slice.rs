@@ -0,0 +1,224 @@
+//! App states built from independent slices.
+//!
+//! Each slice is a `State` of its own, with its own message type and reducer.
+//! The app message has a variant for each slice, converted with `From` like
+//! for `Reactor::downgrade`, and back with `Route`:
+//!
+//! ```ignore
+//! enum Message {
+//!     Todos(todos::Message),
+//!     Filter(filter::Message),
+//! }
+//!
+//! impl From<todos::Message> for Message { ... }
+//!
+//! impl Route<todos::Message> for Message {
+//!     fn route(self) -> Result<todos::Message, Self> {
+//!         match self {
+//!             Message::Todos(message) => Ok(message),
+//!             message => Err(message),
+//!         }
+//!     }
+//! }
+//!
+//! type App = Combined<Message, (todos::Todos, filter::Filter)>;
+//!
+//! fn view(state: &App) -> impl Block<Message = Message> {
+//!     let (ref todos, ref filter) = **state;
+//!     ...
+//! }
+//! ```
+//!
+//! Each slice is given a reactor for its own messages, and only sees the
+//! messages routed to it.
+
+use std::marker::PhantomData;
+use std::ops::{Deref, DerefMut};
+
+use ::{State, Reactor};
+use events::Event;
+
+/// A message that may belong to a slice with messages of type `T`.
+pub trait Route<T>: Sized {
+    /// The message for the slice, or the message itself if it belongs to
+    /// another slice.
+    fn route(self) -> Result<T, Self>;
+}
+
+/// A state made of a tuple of slices.
+///
+/// Dereferences to the tuple of slices.
+pub struct Combined<M, T> {
+    slices: T,
+    _message: PhantomData<fn(M)>,
+}
+
+impl<M, T> Combined<M, T> {
+    /// Combine slices that were already created.
+    pub fn wrap(slices: T) -> Self {
+        Combined {
+            slices,
+            _message: PhantomData,
+        }
+    }
+
+    /// Take the slices out of the state.
+    pub fn into_inner(self) -> T {
+        self.slices
+    }
+}
+
+impl<M, T> Deref for Combined<M, T> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        &self.slices
+    }
+}
+
+impl<M, T> DerefMut for Combined<M, T> {
+    fn deref_mut(&mut self) -> &mut T {
+        &mut self.slices
+    }
+}
+
+macro_rules! impl_combined {
+    ($(($($S:ident: $index:tt),*)),*) => {$(
+        impl<M, $($S),*> State for Combined<M, ($($S,)*)>
+        where
+            M: 'static + Send $(+ From<$S::Message> + Route<$S::Message>)*,
+            $($S: State),*
+        {
+            type Message = M;
+
+            fn new(reactor: Reactor<M>) -> Self {
+                Combined::wrap(($($S::new(reactor.downgrade()),)*))
+            }
+
+            /// Pass a message to the first slice it routes to. Messages that no
+            /// slice accepts are dropped.
+            fn reduce(&mut self, message: M) {
+                $(
+                    let message = match <M as Route<$S::Message>>::route(message) {
+                        Ok(message) => return self.slices.$index.reduce(message),
+                        Err(message) => message,
+                    };
+                )*
+
+                drop(message);
+            }
+
+            fn event(&mut self, event: &Event) {
+                $(self.slices.$index.event(event);)*
+            }
+        }
+    )*}
+}
+
+impl_combined! {
+    (A: 0),
+    (A: 0, B: 1),
+    (A: 0, B: 1, C: 2),
+    (A: 0, B: 1, C: 2, D: 3),
+    (A: 0, B: 1, C: 2, D: 3, E: 4),
+    (A: 0, B: 1, C: 2, D: 3, E: 4, F: 5)
+}
+
+#[cfg(test)]
+mod test {
+    use harness::Harness;
+    use block::{Block, Build};
+    use super::*;
+
+    /// A slice that counts, and doubles its count when asked to by sending
+    /// itself messages.
+    struct Counter {
+        count: u32,
+        reactor: Reactor<u32>,
+    }
+
+    impl State for Counter {
+        type Message = u32;
+
+        fn new(reactor: Reactor<u32>) -> Self {
+            Counter { count: 0, reactor }
+        }
+
+        fn reduce(&mut self, message: u32) {
+            if message == 0 {
+                self.reactor.send(self.count);
+            } else {
+                self.count += message;
+            }
+        }
+    }
+
+    struct Log(Vec<&'static str>);
+
+    impl State for Log {
+        type Message = &'static str;
+
+        fn new(_: Reactor<&'static str>) -> Self {
+            Log(Vec::new())
+        }
+
+        fn reduce(&mut self, message: &'static str) {
+            self.0.push(message);
+        }
+    }
+
+    enum Message {
+        Counter(u32),
+        Log(&'static str),
+    }
+
+    impl From<u32> for Message {
+        fn from(message: u32) -> Self {
+            Message::Counter(message)
+        }
+    }
+
+    impl From<&'static str> for Message {
+        fn from(message: &'static str) -> Self {
+            Message::Log(message)
+        }
+    }
+
+    impl Route<u32> for Message {
+        fn route(self) -> Result<u32, Self> {
+            match self {
+                Message::Counter(message) => Ok(message),
+                message => Err(message),
+            }
+        }
+    }
+
+    impl Route<&'static str> for Message {
+        fn route(self) -> Result<&'static str, Self> {
+            match self {
+                Message::Log(message) => Ok(message),
+                message => Err(message),
+            }
+        }
+    }
+
+    type App = Combined<Message, (Counter, Log)>;
+
+    fn app(state: &App) -> impl Block<Message = Message> {
+        let (ref counter, ref log) = **state;
+
+        Build::new().block((counter.count, log.0.join(",")))
+    }
+
+    #[test]
+    fn route() {
+        let mut harness = Harness::new(app);
+
+        harness.send(Message::Counter(3));
+        harness.send(Message::Log("a"));
+        harness.send(Message::Counter(0));
+        harness.send(Message::Log("b"));
+
+        assert_eq!("6a,b", harness.render().text());
+    }
+}
@@ -1,4 +1,7 @@
-use super::{Double, Color, Unit};
+use std::sync::Arc;
+
+use super::{Double, Color, Percentage, Unit};
+use super::render::Angle;
 
 #[derive(Debug, Clone, Copy)]
 pub enum Size {
@@ -13,24 +16,283 @@ impl Default for Size {
     }
 }
 
+/// How an image is tiled when it is smaller than its block.
+#[derive(Debug, Clone, Copy)]
+pub enum Repeat {
+    None,
+    Horizontal,
+    Vertical,
+    Both,
+}
+
+impl Default for Repeat {
+    fn default() -> Self {
+        Repeat::None
+    }
+}
+
+/// The format of an encoded image.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Format {
+    Png,
+    Jpeg,
+    Gif,
+    Svg,
+    WebP,
+}
+
+impl Format {
+    /// Detect the format of an encoded image from its contents.
+    pub fn detect(bytes: &[u8]) -> Option<Format> {
+        if bytes.starts_with(b"\x89PNG\r\n\x1a\n") {
+            Some(Format::Png)
+        } else if bytes.starts_with(b"\xff\xd8\xff") {
+            Some(Format::Jpeg)
+        } else if bytes.starts_with(b"GIF87a") || bytes.starts_with(b"GIF89a") {
+            Some(Format::Gif)
+        } else if bytes.len() >= 12 && &bytes[..4] == b"RIFF" && &bytes[8..12] == b"WEBP" {
+            Some(Format::WebP)
+        } else if is_svg(bytes) {
+            Some(Format::Svg)
+        } else {
+            None
+        }
+    }
+
+    pub fn mime(&self) -> &'static str {
+        match *self {
+            Format::Png => "image/png",
+            Format::Jpeg => "image/jpeg",
+            Format::Gif => "image/gif",
+            Format::Svg => "image/svg+xml",
+            Format::WebP => "image/webp",
+        }
+    }
+}
+
+/// Whether the start of a document looks like SVG, allowing for an XML
+/// declaration, comments and a doctype before the root element.
+fn is_svg(bytes: &[u8]) -> bool {
+    let start = &bytes[..bytes.len().min(1024)];
+
+    // Skip a byte order mark.
+    let start = if start.starts_with(b"\xef\xbb\xbf") { &start[3..] } else { start };
+
+    let markup = start
+        .iter()
+        .find(|byte| !b" \t\r\n".contains(byte))
+        .map_or(false, |&byte| byte == b'<');
+
+    markup && start.windows(4).any(|window| window == b"<svg")
+}
+
+/// An image background, from the bytes of a PNG, JPEG, GIF, SVG or WebP
+/// image.
+///
+/// The bytes are shared between clones, so styles can be cloned freely.
 #[derive(Debug, Clone)]
-pub enum Background {
-    Color(Color),
-    Image(Vec<u8>, Size),
+pub struct Image {
+    pub bytes: Arc<Vec<u8>>,
+    pub size: Size,
+    pub repeat: Repeat,
+    /// The horizontal and vertical offset of the image within its block.
+    pub position: Double<Unit>,
 }
 
-impl Default for Background {
+impl Image {
+    pub fn new<T>(bytes: T) -> Self where T: Into<Vec<u8>> {
+        Image {
+            bytes: Arc::new(bytes.into()),
+            size: Default::default(),
+            repeat: Default::default(),
+            position: Default::default(),
+        }
+    }
+
+    pub fn format(&self) -> Option<Format> {
+        Format::detect(&self.bytes)
+    }
+
+    pub fn size(mut self, size: Size) -> Self {
+        self.size = size;
+        self
+    }
+
+    pub fn repeat(mut self, repeat: Repeat) -> Self {
+        self.repeat = repeat;
+        self
+    }
+
+    pub fn position(mut self, x: Unit, y: Unit) -> Self {
+        self.position = Double::new(x, y);
+        self
+    }
+}
+
+/// A color at a position along a gradient.
+#[derive(Debug, Clone, Copy)]
+pub struct Stop {
+    pub color: Color,
+    pub position: Percentage,
+}
+
+#[derive(Debug, Clone, Copy)]
+pub enum Shape {
+    Circle,
+    Ellipse,
+}
+
+impl Default for Shape {
     fn default() -> Self {
-        Background::Color(Default::default())
+        Shape::Ellipse
     }
 }
 
+#[derive(Debug, Clone, Copy)]
+pub enum Kind {
+    /// Colors change along a line at an angle, where `0` points up.
+    Linear(Angle),
+    /// Colors change outwards from the center.
+    Radial {
+        shape: Shape,
+        center: Double<Unit>,
+    },
+    /// Colors change around the center, starting at an angle.
+    Conic {
+        from: Angle,
+        center: Double<Unit>,
+    },
+}
+
+/// A gradient between color stops.
+#[derive(Debug, Clone)]
+pub struct Gradient {
+    pub kind: Kind,
+    pub stops: Vec<Stop>,
+}
+
+impl Gradient {
+    pub fn linear(angle: Angle) -> Self {
+        Gradient {
+            kind: Kind::Linear(angle),
+            stops: Vec::new(),
+        }
+    }
+
+    /// A radial gradient centered in its block.
+    pub fn radial(shape: Shape) -> Self {
+        Gradient {
+            kind: Kind::Radial {
+                shape,
+                center: Double::new(Unit::percentage(50.0), Unit::percentage(50.0)),
+            },
+            stops: Vec::new(),
+        }
+    }
+
+    /// A conic gradient centered in its block.
+    pub fn conic(from: Angle) -> Self {
+        Gradient {
+            kind: Kind::Conic {
+                from,
+                center: Double::new(Unit::percentage(50.0), Unit::percentage(50.0)),
+            },
+            stops: Vec::new(),
+        }
+    }
+
+    /// Move the center of a radial or conic gradient.
+    pub fn center(mut self, x: Unit, y: Unit) -> Self {
+        match self.kind {
+            Kind::Radial { ref mut center, .. } | Kind::Conic { ref mut center, .. } => {
+                *center = Double::new(x, y);
+            }
+            Kind::Linear(..) => {}
+        }
+
+        self
+    }
+
+    /// Add a color stop after the existing ones.
+    pub fn stop<T>(mut self, color: Color, position: T) -> Self where T: Into<Percentage> {
+        self.stops.push(Stop {
+            color,
+            position: position.into(),
+        });
+
+        self
+    }
+}
+
+/// A layer of background drawn over the background color.
+#[derive(Debug, Clone)]
+pub enum Layer {
+    Image(Image),
+    Gradient(Gradient),
+}
+
+/// A background color, with any number of layers drawn over it.
+#[derive(Debug, Default, Clone)]
+pub struct Background {
+    pub color: Color,
+    /// The layers, with the first drawn on top.
+    pub layers: Vec<Layer>,
+}
+
 impl Background {
+    /// A plain color background.
+    pub fn from_color(color: Color) -> Self {
+        Background {
+            color,
+            layers: Vec::new(),
+        }
+    }
+
+    /// A background of a single image over the default color.
+    pub fn from_image(image: Vec<u8>, size: Size) -> Self {
+        let mut background = Background::default();
+        background.image(image, size);
+        background
+    }
+
     pub fn color(&mut self, color: Color) {
-        *self = Background::Color(color);
+        self.color = color;
     }
 
+    /// Replace the layers with an image.
     pub fn image(&mut self, image: Vec<u8>, size: Size) {
-        *self = Background::Image(image, size);
+        self.layers = vec![Layer::Image(Image::new(image).size(size))];
+    }
+
+    /// Add a layer below the existing ones.
+    pub fn layer<T>(&mut self, layer: T) where T: Into<Layer> {
+        self.layers.push(layer.into());
+    }
+}
+
+impl From<Image> for Layer {
+    fn from(image: Image) -> Self {
+        Layer::Image(image)
+    }
+}
+
+impl From<Gradient> for Layer {
+    fn from(gradient: Gradient) -> Self {
+        Layer::Gradient(gradient)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn detect() {
+        assert_eq!(Some(Format::Png), Format::detect(b"\x89PNG\r\n\x1a\n\0\0\0\rIHDR"));
+        assert_eq!(Some(Format::Jpeg), Format::detect(b"\xff\xd8\xff\xe0\0\x10JFIF"));
+        assert_eq!(Some(Format::Gif), Format::detect(b"GIF89a\x01\0\x01\0"));
+        assert_eq!(Some(Format::WebP), Format::detect(b"RIFF\x24\0\0\0WEBPVP8 "));
+        assert_eq!(Some(Format::Svg), Format::detect(b"<?xml version=\"1.0\"?>\n<svg></svg>"));
+        assert_eq!(None, Format::detect(b"plain text"));
     }
 }
@@ -1,9 +1,85 @@
 use super::{EdgeMode, Length, Quadruple, Color, Unit};
 
+#[derive(Debug, Clone, Copy)]
+pub enum LineStyle {
+    None,
+    Solid,
+    Dashed,
+    Dotted,
+    Double,
+}
+
+impl Default for LineStyle {
+    /// Solid, so that a border is drawn as soon as it has a width.
+    fn default() -> Self {
+        LineStyle::Solid
+    }
+}
+
+/// One side of a border.
 #[derive(Debug, Default, Clone, Copy)]
-pub struct Border {
-    pub mode: EdgeMode,
+pub struct Side {
     pub width: Length,
     pub color: Color,
+    pub style: LineStyle,
+}
+
+impl Side {
+    pub fn new<T>(width: T, color: Color, style: LineStyle) -> Self where T: Into<Length> {
+        Side {
+            width: width.into(),
+            color,
+            style,
+        }
+    }
+}
+
+#[derive(Debug, Default, Clone, Copy)]
+pub struct Border {
+    pub mode: EdgeMode,
+    /// The top, right, bottom and left sides.
+    pub sides: Quadruple<Side>,
+    /// The top left, top right, bottom right and bottom left corners.
     pub radius: Quadruple<Unit>,
 }
+
+impl Border {
+    /// Use the same border on every side.
+    pub fn all(&mut self, side: Side) {
+        self.sides = (side, side, side, side).into();
+    }
+
+    pub fn sides<T>(&mut self, sides: T) where T: Into<(Side, Side, Side, Side)> {
+        self.sides = sides.into().into();
+    }
+
+    /// Round every corner by the same radius.
+    pub fn round(&mut self, radius: Unit) {
+        self.radius = (radius, radius, radius, radius).into();
+    }
+
+    pub fn radius<T>(&mut self, radius: T) where T: Into<(Unit, Unit, Unit, Unit)> {
+        self.radius = radius.into().into();
+    }
+}
+
+/// A line drawn around a block without taking up space, such as a focus
+/// ring.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct Outline {
+    /// The line, or `None` to leave the outline to the browser, which draws
+    /// focus rings with it.
+    pub side: Option<Side>,
+    /// The distance between the outline and the border.
+    pub offset: Length,
+}
+
+impl Outline {
+    pub fn set(&mut self, side: Side) {
+        self.side = Some(side);
+    }
+
+    pub fn offset<T>(&mut self, offset: T) where T: Into<Length> {
+        self.offset = offset.into();
+    }
+}
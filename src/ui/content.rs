@@ -15,10 +15,11 @@ impl Default for AlignMode {
     }
 }
 
+/// The alignment of children along the flow, and across it.
 #[derive(Debug, Default, Clone, Copy)]
 pub struct Align(pub Double<AlignMode>);
 
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, PartialEq)]
 pub enum Direction {
     Forwards,
     Backwards,
@@ -30,7 +31,7 @@ impl Default for Direction {
     }
 }
 
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, PartialEq)]
 pub enum Flow {
     Row(Direction),
     Column(Direction),
@@ -42,6 +43,23 @@ impl Default for Flow {
     }
 }
 
+/// What happens to children that do not fit along the flow.
+#[derive(Debug, Clone, Copy)]
+pub enum Wrap {
+    /// Keep every child on one line.
+    None,
+    /// Start a new line after the current one.
+    Forwards,
+    /// Start a new line before the current one.
+    Backwards,
+}
+
+impl Default for Wrap {
+    fn default() -> Wrap {
+        Wrap::None
+    }
+}
+
 #[derive(Debug, Clone, Copy)]
 pub struct Scale(pub Percentage);
 
@@ -56,4 +74,20 @@ pub struct Content {
     pub scale: Scale,
     pub align: Align,
     pub flow: Flow,
+    pub wrap: Wrap,
+}
+
+impl Content {
+    pub fn flow(&mut self, flow: Flow) {
+        self.flow = flow;
+    }
+
+    /// Align children along the flow with `main`, and across it with `cross`.
+    pub fn align(&mut self, main: AlignMode, cross: AlignMode) {
+        self.align = Align((main, cross).into());
+    }
+
+    pub fn wrap(&mut self, wrap: Wrap) {
+        self.wrap = wrap;
+    }
 }
@@ -1,4 +1,4 @@
-use super::Color;
+use super::{Color, Length, Unit};
 
 #[derive(Debug, Clone)]
 pub enum Weight {
@@ -21,12 +21,57 @@ pub enum Family {
     Name(String),
 }
 
+#[derive(Debug, Clone, Copy)]
+pub enum Align {
+    Start,
+    Center,
+    End,
+    Justify,
+}
+
+/// Lines drawn along the text.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct Decoration {
+    pub underline: bool,
+    pub overline: bool,
+    pub strike: bool,
+}
+
+#[derive(Debug, Clone, Copy)]
+pub enum Transform {
+    None,
+    Uppercase,
+    Lowercase,
+    Capitalize,
+}
+
+/// How spaces and line breaks in the text are kept.
+#[derive(Debug, Clone, Copy)]
+pub enum WhiteSpace {
+    /// Collapse runs of spaces and line breaks into a single space.
+    Collapse,
+    /// Keep every space and line break.
+    Preserve,
+    /// Keep line breaks, but collapse runs of spaces.
+    PreserveLines,
+}
+
 #[derive(Debug, Clone)]
 pub struct Font {
     pub family: Family,
     pub weight: Weight,
     pub style: Style,
     pub color: Color,
+    /// The font size, or `None` to inherit it.
+    pub size: Option<Unit>,
+    /// The height of a line, or `None` for the default of the font.
+    pub line_height: Option<Unit>,
+    pub letter_spacing: Length,
+    pub word_spacing: Length,
+    pub align: Align,
+    pub decoration: Decoration,
+    pub transform: Transform,
+    pub white_space: WhiteSpace,
 }
 
 impl Default for Font {
@@ -36,6 +81,14 @@ impl Default for Font {
             weight: Weight::Regular,
             style: Style::Regular,
             color: Color::black(),
+            size: None,
+            line_height: None,
+            letter_spacing: Default::default(),
+            word_spacing: Default::default(),
+            align: Align::Start,
+            decoration: Default::default(),
+            transform: Transform::None,
+            white_space: WhiteSpace::Collapse,
         }
     }
 }
@@ -44,4 +97,44 @@ impl Font {
     pub fn color(&mut self, color: Color) {
         self.color = color;
     }
+
+    pub fn size(&mut self, size: Unit) {
+        self.size = Some(size);
+    }
+
+    pub fn line_height(&mut self, line_height: Unit) {
+        self.line_height = Some(line_height);
+    }
+
+    pub fn letter_spacing<T>(&mut self, spacing: T) where T: Into<Length> {
+        self.letter_spacing = spacing.into();
+    }
+
+    pub fn word_spacing<T>(&mut self, spacing: T) where T: Into<Length> {
+        self.word_spacing = spacing.into();
+    }
+
+    pub fn align(&mut self, align: Align) {
+        self.align = align;
+    }
+
+    pub fn underline(&mut self) {
+        self.decoration.underline = true;
+    }
+
+    pub fn overline(&mut self) {
+        self.decoration.overline = true;
+    }
+
+    pub fn strike(&mut self) {
+        self.decoration.strike = true;
+    }
+
+    pub fn transform(&mut self, transform: Transform) {
+        self.transform = transform;
+    }
+
+    pub fn white_space(&mut self, white_space: WhiteSpace) {
+        self.white_space = white_space;
+    }
 }
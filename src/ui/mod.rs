@@ -8,18 +8,20 @@ pub mod shadow;
 pub mod render;
 pub mod reactive;
 pub mod font;
+pub mod text;
 
 // Enums should be re-exported.
 use self::position::Position;
 use self::size::Size;
 use self::content::Content;
 use self::spacing::Spacing;
-use self::border::Border;
+use self::border::{Border, Outline};
 pub use self::background::Background;
-use self::shadow::Shadow;
+use self::shadow::Shadows;
 use self::render::Render;
 use self::reactive::Reactive;
 use self::font::Font;
+use self::text::Text;
 
 pub use self::reactive::Cursor;
 
@@ -101,6 +103,24 @@ pub struct Double<T> {
     b: T,
 }
 
+impl<T> Double<T> {
+    pub fn new(a: T, b: T) -> Self {
+        Double { a, b }
+    }
+}
+
+impl<T> Into<(T, T)> for Double<T> {
+    fn into(self) -> (T, T) {
+        (self.a, self.b)
+    }
+}
+
+impl<T> From<(T, T)> for Double<T> {
+    fn from((a, b): (T, T)) -> Self {
+        Double { a, b }
+    }
+}
+
 #[derive(Debug, Default, Copy, Clone)]
 pub struct Quadruple<T> {
     a: T,
@@ -109,6 +129,17 @@ pub struct Quadruple<T> {
     d: T,
 }
 
+impl<T> Quadruple<T> {
+    pub fn map<U, F>(self, f: F) -> Quadruple<U> where F: Fn(T) -> U {
+        Quadruple {
+            a: f(self.a),
+            b: f(self.b),
+            c: f(self.c),
+            d: f(self.d),
+        }
+    }
+}
+
 impl<T> Into<(T, T, T, T)> for Quadruple<T> {
     fn into(self) -> (T, T, T, T) {
         (self.a, self.b, self.c, self.d)
@@ -150,11 +181,13 @@ pub struct Style {
     pub content: content::Content,
     pub spacing: spacing::Spacing,
     pub border: border::Border,
+    pub outline: border::Outline,
     pub background: background::Background,
-    pub shadow: shadow::Shadow,
+    pub shadow: shadow::Shadows,
     pub render: render::Render,
     pub reactive: reactive::Reactive,
     pub font: font::Font,
+    pub text: text::Text,
 }
 
 impl Style {
@@ -31,9 +31,17 @@ pub enum Hook {
 
 #[derive(Debug, Clone, Copy)]
 pub enum Position {
+    /// Laid out with its siblings.
     Passthrough,
+    /// Laid out with its siblings, and the anchor of hooked descendants.
     Anchor,
+    /// Taken out of the layout and offset from the nearest anchor.
     Hook(Hook),
+    /// Taken out of the layout and offset from the viewport.
+    Fixed(Hook),
+    /// Laid out with its siblings, but kept within the offsets from the edges
+    /// of the viewport while its parent is scrolled.
+    Sticky(Hook),
 }
 
 impl Default for Position {
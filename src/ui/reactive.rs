@@ -12,7 +12,7 @@ impl Default for Cursor {
 
 #[derive(Clone, Default, Debug)]
 pub struct Subset {
-    pub shadow: super::shadow::Shadow,
+    pub shadow: super::shadow::Shadows,
     pub spacing: super::spacing::Spacing,
 }
 
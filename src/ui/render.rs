@@ -1,8 +1,11 @@
+use std::f32::consts::PI;
+
 use super::{Length, Percentage, Unit};
 
 #[derive(Debug, Default, Clone, Copy)]
 pub struct Opacity(pub Percentage);
 
+/// The stacking order, where blocks with a greater depth are drawn on top.
 #[derive(Debug, Default, Clone, Copy)]
 pub struct Depth(pub Length);
 
@@ -13,9 +16,36 @@ pub struct Translation {
     pub z: Unit,
 }
 
-#[derive(Debug, Default, Clone, Copy)]
+/// An angle, stored in degrees.
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
 pub struct Angle(f32);
 
+impl Angle {
+    pub fn degrees(degrees: f32) -> Self {
+        Angle(degrees)
+    }
+
+    pub fn radians(radians: f32) -> Self {
+        Angle(radians * 180.0 / PI)
+    }
+
+    /// An angle in full turns, where `1.0` is `360` degrees.
+    pub fn turns(turns: f32) -> Self {
+        Angle(turns * 360.0)
+    }
+
+    pub fn as_degrees(&self) -> f32 {
+        self.0
+    }
+
+    pub fn as_radians(&self) -> f32 {
+        self.0 * PI / 180.0
+    }
+}
+
+/// A rotation about an axis through the center of the block.
+///
+/// An axis of all zeroes rotates in the plane of the screen.
 #[derive(Debug, Default, Clone, Copy)]
 pub struct Rotation {
     pub axis: [Unit; 3],
@@ -33,3 +63,30 @@ pub struct Render {
     pub depth: Depth,
     pub opacity: Opacity,
 }
+
+impl Render {
+    pub fn translate(&mut self, x: Unit, y: Unit) {
+        self.translation.x = x;
+        self.translation.y = y;
+    }
+
+    /// Rotate in the plane of the screen.
+    pub fn rotate(&mut self, angle: Angle) {
+        self.rotation = Rotation {
+            axis: Default::default(),
+            angle,
+        };
+    }
+
+    pub fn scale<T>(&mut self, scale: T) where T: Into<Percentage> {
+        self.scale = Scale(scale.into());
+    }
+
+    pub fn depth<T>(&mut self, depth: T) where T: Into<Length> {
+        self.depth = Depth(depth.into());
+    }
+
+    pub fn opacity<T>(&mut self, opacity: T) where T: Into<Percentage> {
+        self.opacity = Opacity(opacity.into());
+    }
+}
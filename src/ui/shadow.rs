@@ -1,9 +1,61 @@
-use super::{Double, EdgeMode, Length};
+use super::{Color, Double, EdgeMode, Length};
 
-#[derive(Debug, Default, Clone, Copy)]
+/// One layer of shadow.
+///
+/// An outset shadow is cast outside of the block, and an inset one inside of
+/// it.
+#[derive(Debug, Clone, Copy)]
 pub struct Shadow {
     pub mode: EdgeMode,
     pub offset: Double<Length>,
     pub blur: Length,
     pub spread: Length,
+    pub color: Color,
+}
+
+impl Default for Shadow {
+    fn default() -> Self {
+        Shadow {
+            mode: Default::default(),
+            offset: Default::default(),
+            blur: Default::default(),
+            spread: Default::default(),
+            color: Color::black(),
+        }
+    }
+}
+
+impl Shadow {
+    pub fn new<T, U>(offset: (T, T), blur: U, color: Color) -> Self
+    where
+        T: Into<Length>,
+        U: Into<Length>,
+    {
+        Shadow {
+            offset: Double::new(offset.0.into(), offset.1.into()),
+            blur: blur.into(),
+            color,
+            .. Default::default()
+        }
+    }
+
+    pub fn inset(mut self) -> Self {
+        self.mode = EdgeMode::Inset;
+        self
+    }
+
+    pub fn spread<T>(mut self, spread: T) -> Self where T: Into<Length> {
+        self.spread = spread.into();
+        self
+    }
+}
+
+/// Layers of shadow, with the first drawn on top.
+#[derive(Debug, Default, Clone)]
+pub struct Shadows(pub Vec<Shadow>);
+
+impl Shadows {
+    pub fn push(&mut self, shadow: Shadow) {
+        self.0.push(shadow);
+    }
 }
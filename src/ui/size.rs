@@ -1,21 +1,31 @@
-use super::{Length, Unit};
+use super::Unit;
 
 #[derive(Debug, Clone, Copy)]
 pub enum Flex {
+    /// Fill the space available in the parent.
     Push,
     Limit(Unit),
 }
 
 #[derive(Debug, Clone, Copy)]
 pub struct Sizing {
-    pub target: Unit,
+    /// The preferred size, or `None` to size to the content.
+    pub target: Option<Unit>,
     pub min: Flex,
     pub max: Flex,
 }
 
 impl Sizing {
     pub fn target(&mut self, target: Unit) {
-        self.target = target;
+        self.target = Some(target);
+    }
+
+    pub fn min(&mut self, min: Flex) {
+        self.min = min;
+    }
+
+    pub fn max(&mut self, max: Flex) {
+        self.max = max;
     }
 }
 
@@ -28,7 +38,7 @@ pub struct Size {
 impl Default for Size {
     fn default() -> Self {
         let sizing = Sizing {
-            target: Unit::spx(0.0),
+            target: None,
             min: Flex::Limit(Unit::spx(0.0)),
             max: Flex::Limit(Unit::spx(1.0 / 0.0)),
         };
@@ -0,0 +1,68 @@
+/// Where lines of text may break.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Wrap {
+    /// Break lines between words.
+    Words,
+    /// Break lines between words, and inside words that do not fit on a line
+    /// of their own.
+    Anywhere,
+    /// Keep the text on a single line, except for preserved line breaks.
+    None,
+}
+
+/// What happens to text that does not fit in the block.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Overflow {
+    /// Draw it outside of the block.
+    Visible,
+    /// Cut it off at the edge of the block.
+    Clip,
+    /// Cut it off, and end the last visible line with an ellipsis.
+    Ellipsis,
+}
+
+/// The layout of the text in a block.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Text {
+    pub wrap: Wrap,
+    pub overflow: Overflow,
+    /// The number of lines to show at most, or `None` to show every line.
+    ///
+    /// Clamped text is laid out as a single paragraph rather than as a flex
+    /// container, so it should not be used on blocks with block children.
+    pub lines: Option<u32>,
+}
+
+impl Default for Text {
+    fn default() -> Self {
+        Text {
+            wrap: Wrap::Words,
+            overflow: Overflow::Visible,
+            lines: None,
+        }
+    }
+}
+
+impl Text {
+    pub fn wrap(&mut self, wrap: Wrap) {
+        self.wrap = wrap;
+    }
+
+    pub fn overflow(&mut self, overflow: Overflow) {
+        self.overflow = overflow;
+    }
+
+    /// Keep the text on one line, ending it with an ellipsis if it is too
+    /// long.
+    pub fn truncate(&mut self) {
+        self.wrap = Wrap::None;
+        self.overflow = Overflow::Ellipsis;
+    }
+
+    /// Show at most `lines` lines, ending the last one with an ellipsis if
+    /// there is more text.
+    pub fn clamp(&mut self, lines: u32) {
+        self.overflow = Overflow::Ellipsis;
+        self.lines = Some(lines);
+    }
+}
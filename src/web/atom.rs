@@ -1,24 +1,27 @@
 use std::collections::HashSet;
 use std::sync::{Arc, Mutex};
 
-use events::{Event, Coordinates, Button};
-use block::{Block, Child};
+use ui::content::Flow;
+use events::{Event, Kind, Coordinates, Button, DefaultEvents};
+use block::bake::Act;
 use super::ffi::{self, AtomId, Attribute, EventType};
-use super::{Candidate, Rendered, Update};
+use super::{Candidate, Handler, Rendered, Update};
 
 /// A wrapper to interface with FFI atoms.
-pub struct Atom {
+pub struct Atom<M> {
     id: AtomId,
-    content: Rendered,
+    content: Rendered<M>,
+    /// The memoized node this atom was last upgraded to, if any.
+    memo: Option<(u64, usize, Flow)>,
 }
 
-impl Drop for Atom {
+impl<M> Drop for Atom<M> {
     fn drop(&mut self) {
         ffi::delete_node(self.id);
     }
 }
 
-impl Atom {
+impl<M> Atom<M> where M: 'static + Send {
     pub fn mount() -> Self {
         Self {
             id: AtomId::root(),
@@ -29,15 +32,17 @@ impl Atom {
                 attributes: vec![
                     Attribute::new("id", ffi::mount_id())
                 ],
+                event_handler: Arc::new(Mutex::new(Box::new(Act::new(DefaultEvents::new())))),
                 children: vec![],
-            }
+            },
+            memo: None,
         }
     }
 
     /// Construct a new node under a parent node.
-    fn new<U>(candidate: Candidate<U::Message>, parent: AtomId, update: U) -> Self
+    fn new<U>(candidate: Candidate<M>, parent: AtomId, update: U) -> Self
     where
-        U: Update,
+        U: Update<Message = M>,
     {
         match candidate {
             Candidate::Text(text) => {
@@ -45,10 +50,18 @@ impl Atom {
 
                 Self {
                     id,
-                    content: Rendered::Text(text)
+                    content: Rendered::Text(text),
+                    memo: None,
                 }
             }
 
+            Candidate::Memo { key, nodes } => {
+                let candidate = nodes.lock().unwrap().take(key.1);
+                let mut atom = Atom::new(candidate, parent, update);
+                atom.memo = Some(key);
+                atom
+            }
+
             Candidate::Element { children, attributes, event_handler } => {
                 let event_handler = Arc::new(Mutex::new(event_handler));
                 let id = ffi::create_element(attributes.clone(), parent);
@@ -59,62 +72,17 @@ impl Atom {
                     .collect();
 
                 let mut registered_events = HashSet::new();
-
-                let test_event = event_handler.lock().unwrap();
-
-                if test_event.event(Event::Click(Coordinates { x: 0, y: 0 })).is_some() {
-                    registered_events.insert(EventType::Click);
-
-                    let event_handler = event_handler.clone();
-                    let update = update.clone();
-
-                    #[derive(Deserialize)]
-                    struct Data {
-                        x: u32,
-                        y: u32,
-                    }
-
-                    ffi::create_event(id, EventType::Click, move |Data { x, y }: Data| {
-                        let guard = event_handler.lock().unwrap();
-                        if let Some(msg) = guard.event(Event::Click(Coordinates { x, y })) {
-                            update.reduce(msg);
-                        }
-                    });
-                } else if test_event.event(Event::MouseDown(Coordinates { x: 0, y: 0 }, Button::Left)).is_some() {
-                    registered_events.insert(EventType::MouseDown);
-
-                    let event_handler = event_handler.clone();
-                    let update = update.clone();
-
-                    #[derive(Deserialize)]
-                    struct Data {
-                        button: u32,
-                        x: u32,
-                        y: u32,
-                    }
-
-                    ffi::create_event(id, EventType::MouseDown, move |Data { x, y, button }: Data| {
-                        let guard = event_handler.lock().unwrap();
-                        let button = match button {
-                            0 => Button::Left,
-                            1 => Button::Middle,
-                            2 => Button::Right,
-                            _ => return,
-                        };
-
-                        if let Some(msg) = guard.event(Event::MouseDown(Coordinates { x, y }, button)) {
-                            update.reduce(msg);
-                        }
-                    });
-                }
+                listen(id, &event_handler, &mut registered_events, &update);
 
                 Atom {
                     id,
                     content: Rendered::Element {
                         attributes,
                         children,
+                        event_handler,
                         registered_events,
-                    }
+                    },
+                    memo: None,
                 }
             }
         }
@@ -123,15 +91,33 @@ impl Atom {
     /// Upgrade the current node to match the candidate tree.
     ///
     /// This implements the tree diffing algorithm.
-    pub fn upgrade<U>(&mut self, candidate: Candidate<U::Message>, update: U)
+    pub fn upgrade<U>(&mut self, candidate: Candidate<M>, update: U)
     where
-        U: Update,
+        U: Update<Message = M>,
     {
         // First we move the updated data out of the candidate tree. Then, we
         // use different ref patterns depending on how we need to update the
         // content. This is to avoid bind by-move and by-ref in the same
         // pattern.
         match candidate {
+            Candidate::Memo { key, nodes } => {
+                // The node is unchanged since it was last rendered.
+                if self.memo == Some(key) {
+                    return;
+                }
+
+                let candidate = nodes.lock().unwrap().take(key.1);
+                self.upgrade(candidate, update);
+                self.memo = Some(key);
+                return;
+            }
+
+            _ => self.memo = None,
+        }
+
+        match candidate {
+            Candidate::Memo { .. } => unreachable!(),
+
             Candidate::Text(new_text) => {
                 match &mut self.content {
                     &mut Rendered::Text(ref mut old_text) => {
@@ -150,7 +136,7 @@ impl Atom {
 
             Candidate::Element {
                 attributes: new_attributes,
-                children: mut new_children,
+                children: new_children,
                 event_handler,
             } => {
                 match &mut self.content {
@@ -163,44 +149,135 @@ impl Atom {
                             .map(|candidate| Atom::new(candidate, id, update.clone()))
                             .collect();
 
+                        let event_handler = Arc::new(Mutex::new(event_handler));
+                        let mut registered_events = HashSet::new();
+                        listen(id, &event_handler, &mut registered_events, &update);
+
                         **element = Rendered::Element {
                             attributes: new_attributes,
                             children: new_children,
-                            registered_events: HashSet::new(),
+                            event_handler,
+                            registered_events,
                         };
                     }
 
-                    &mut Rendered::Element { ref mut children, ref mut attributes, .. } => {
+                    &mut Rendered::Element {
+                        ref mut children,
+                        ref mut attributes,
+                        event_handler: ref old_handler,
+                        ref mut registered_events,
+                    } => {
                         ffi::update_element(self.id, new_attributes.clone());
                         *attributes = new_attributes;
 
-                        let num_nodes = children.len();
+                        // Registered callbacks look the handler up when they
+                        // run, so replacing it is enough to pick up new
+                        // messages.
+                        *old_handler.lock().unwrap() = event_handler;
+                        listen(self.id, old_handler, registered_events, &update);
 
-                        if num_nodes > new_children.len() {
-                            // Drop the old nodes.
-                            let _ = children.drain(num_nodes - 1..);
-                        }
+                        // Drop the old nodes.
+                        children.truncate(new_children.len());
 
-                        // The new children array.
-                        let mut updated_children = Vec::new();
+                        let mut new_children = new_children.into_iter();
 
-                        // Push the existing children and upgrade the nodes.
-                        for _ in 0..num_nodes {
-                            let mut node = children.remove(0);
-                            node.upgrade(new_children.remove(0), update.clone());
-                            updated_children.push(node);
+                        // Upgrade the existing nodes.
+                        for (node, candidate) in children.iter_mut().zip(&mut new_children) {
+                            node.upgrade(candidate, update.clone());
                         }
 
-                        // Create new nodes from any remaining additional children
-                        for _ in num_nodes..new_children.len() {
-                            let atom = Atom::new(new_children.remove(0), self.id, update.clone());
-                            updated_children.push(atom);
+                        // Create new nodes from any remaining additional children.
+                        for candidate in new_children {
+                            children.push(Atom::new(candidate, self.id, update.clone()));
                         }
-
-                        *children = updated_children;
                     }
                 }
             }
         }
     }
 }
+
+/// Register callbacks for any events the handler responds to that are not
+/// registered yet.
+fn listen<U>(id: AtomId, handler: &Handler<U::Message>, registered: &mut HashSet<EventType>, update: &U)
+where
+    U: Update,
+{
+    let (click, down, up) = {
+        let handler = handler.lock().unwrap();
+
+        (
+            handler.handles(Kind::Click),
+            handler.handles(Kind::MouseDown),
+            handler.handles(Kind::MouseUp),
+        )
+    };
+
+    if click && registered.insert(EventType::Click) {
+        let handler = handler.clone();
+        let update = update.clone();
+
+        #[derive(Deserialize)]
+        struct Data {
+            x: u32,
+            y: u32,
+        }
+
+        ffi::create_event(id, EventType::Click, move |Data { x, y }: Data| {
+            dispatch(&handler, Event::Click(Coordinates { x, y }), &update);
+        });
+    }
+
+    if down && registered.insert(EventType::MouseDown) {
+        listen_button(id, EventType::MouseDown, Event::MouseDown, handler, update);
+    }
+
+    if up && registered.insert(EventType::MouseUp) {
+        listen_button(id, EventType::MouseUp, Event::MouseUp, handler, update);
+    }
+}
+
+/// Register a callback for a mouse button event.
+fn listen_button<U>(
+    id: AtomId,
+    kind: EventType,
+    event: fn(Coordinates, Button) -> Event,
+    handler: &Handler<U::Message>,
+    update: &U,
+)
+where
+    U: Update,
+{
+    let handler = handler.clone();
+    let update = update.clone();
+
+    #[derive(Deserialize)]
+    struct Data {
+        button: u32,
+        x: u32,
+        y: u32,
+    }
+
+    ffi::create_event(id, kind, move |Data { x, y, button }: Data| {
+        let button = match button {
+            0 => Button::Left,
+            1 => Button::Middle,
+            2 => Button::Right,
+            _ => return,
+        };
+
+        dispatch(&handler, event(Coordinates { x, y }, button), &update);
+    });
+}
+
+/// Pass an event to a handler and run the resulting actions in order.
+fn dispatch<U>(handler: &Handler<U::Message>, event: Event, update: &U) where U: Update {
+    update.event(event);
+
+    // Release the handler before dispatching.
+    let actions = handler.lock().unwrap().event(event);
+
+    for action in actions {
+        action.dispatch(update);
+    }
+}
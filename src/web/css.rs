@@ -1,6 +1,18 @@
-use ui::{self, font, Quadruple, EdgeMode, Color, Length, Style};
-use ui::border::Border;
+use std::collections::VecDeque;
+use std::collections::hash_map::DefaultHasher;
+use std::hash::{Hash, Hasher};
+use std::sync::{Arc, Mutex};
+
+use ui::{self, font, Quadruple, EdgeMode, Color, Length, Percentage, Unit, Style};
+use ui::border::{Border, Outline, LineStyle};
 use ui::spacing::Spacing;
+use ui::size::{Size, Sizing, Flex};
+use ui::content::{Content, Flow, Direction, AlignMode, Wrap};
+use ui::position::{Position, Hook, SingleHook, VerticalHook, HorizontalHook};
+use ui::render::{Render, Angle};
+use ui::text::{self, Text};
+use ui::shadow::{Shadow, Shadows};
+use ui::background::{Image, Size as ImageSize, Repeat, Gradient, Kind, Shape, Layer};
 
 use super::ffi;
 
@@ -58,6 +70,21 @@ impl Inline for Length {
     }
 }
 
+impl Inline for Percentage {
+    fn inline(&self) -> String {
+        format!("{}%", self.0)
+    }
+}
+
+impl Inline for Unit {
+    fn inline(&self) -> String {
+        match *self {
+            Unit::Length(ref length) => length.inline(),
+            Unit::Percentage(ref percentage) => percentage.inline(),
+        }
+    }
+}
+
 impl<T> Inline for Quadruple<T> where T: Inline + Clone {
     fn inline(&self) -> String {
         let (a, b, c, d) = self.clone().into();
@@ -95,21 +122,433 @@ impl Inline for font::Font {
 
         css.property("color", self.color.inline());
 
+        // Without a size, the scale of the content applies.
+        if let Some(ref size) = self.size {
+            css.property("font-size", size.inline());
+        }
+
+        css.property("line-height", match self.line_height {
+            Some(ref height) => height.inline(),
+            None => String::from("normal"),
+        });
+
+        css.property("letter-spacing", self.letter_spacing.inline());
+        css.property("word-spacing", self.word_spacing.inline());
+
+        css.property("text-align", match self.align {
+            font::Align::Start => "start",
+            font::Align::Center => "center",
+            font::Align::End => "end",
+            font::Align::Justify => "justify",
+        });
+
+        let mut lines = Vec::new();
+
+        if self.decoration.underline {
+            lines.push("underline");
+        }
+
+        if self.decoration.overline {
+            lines.push("overline");
+        }
+
+        if self.decoration.strike {
+            lines.push("line-through");
+        }
+
+        if lines.is_empty() {
+            lines.push("none");
+        }
+
+        css.property("text-decoration", lines.join(" "));
+
+        css.property("text-transform", match self.transform {
+            font::Transform::None => "none",
+            font::Transform::Uppercase => "uppercase",
+            font::Transform::Lowercase => "lowercase",
+            font::Transform::Capitalize => "capitalize",
+        });
+
+        css.property("white-space", match self.white_space {
+            font::WhiteSpace::Collapse => "normal",
+            font::WhiteSpace::Preserve => "pre-wrap",
+            font::WhiteSpace::PreserveLines => "pre-line",
+        });
+
         css.render()
     }
 }
 
+/// The layout of the text, which replaces the white space handling of the
+/// font when lines may not wrap.
+///
+/// Only what differs from the default layout is rendered.
+fn text_layout(text: &Text, white_space: font::WhiteSpace) -> String {
+    let mut css = Css::new();
+
+    match (text.wrap, white_space) {
+        (text::Wrap::None, font::WhiteSpace::Collapse) => css.property("white-space", "nowrap"),
+        (text::Wrap::None, font::WhiteSpace::Preserve) => css.property("white-space", "pre"),
+        // Keep the preserved line breaks and only stop wrapping long lines,
+        // which `white-space` alone cannot express. Browsers without the
+        // longhand still stop wrapping, but lose the line breaks.
+        (text::Wrap::None, font::WhiteSpace::PreserveLines) => {
+            css.property("white-space", "nowrap");
+            css.property("white-space-collapse", "preserve-breaks");
+        }
+        _ => {}
+    }
+
+    if text.wrap == text::Wrap::Anywhere {
+        css.property("overflow-wrap", "break-word");
+    }
+
+    if text.overflow != text::Overflow::Visible {
+        css.property("overflow", "hidden");
+    }
+
+    if text.overflow == text::Overflow::Ellipsis {
+        css.property("text-overflow", "ellipsis");
+    }
+
+    // Neither applies to flex containers, so clamped or truncated text is
+    // laid out as a paragraph instead.
+    if let Some(lines) = text.lines {
+        css.property("display", "-webkit-box");
+        css.property("-webkit-box-orient", "vertical");
+        css.property("-webkit-line-clamp", lines.to_string());
+    } else if text.overflow == text::Overflow::Ellipsis {
+        css.property("display", "block");
+    }
+
+    css.render()
+}
+
+impl Inline for LineStyle {
+    fn inline(&self) -> String {
+        String::from(match *self {
+            LineStyle::None => "none",
+            LineStyle::Solid => "solid",
+            LineStyle::Dashed => "dashed",
+            LineStyle::Dotted => "dotted",
+            LineStyle::Double => "double",
+        })
+    }
+}
+
 impl Inline for Border {
     fn inline(&self) -> String {
         let mut css = Css::new();
 
-        css.property("border-color", self.color.inline());
-        css.property("border-width", self.width.inline());
+        css.property("border-color", self.sides.map(|side| side.color).inline());
+        css.property("border-width", self.sides.map(|side| side.width).inline());
+        css.property("border-style", self.sides.map(|side| side.style).inline());
+        css.property("border-radius", self.radius.inline());
         css.property("box-sizing", match self.mode {
             EdgeMode::Inset => "border-box",
             EdgeMode::Outset => "content-box",
         });
-        css.property("border-style", String::from("solid"));
+
+        css.render()
+    }
+}
+
+impl Inline for Outline {
+    fn inline(&self) -> String {
+        let mut css = Css::new();
+
+        if let Some(ref side) = self.side {
+            css.property("outline-color", side.color.inline());
+            css.property("outline-width", side.width.inline());
+            css.property("outline-style", side.style.inline());
+            css.property("outline-offset", self.offset.inline());
+        }
+
+        css.render()
+    }
+}
+
+/// Whether a unit is an actual limit, rather than an infinite one.
+fn finite(unit: &Unit) -> bool {
+    match *unit {
+        Unit::Length(Length(length)) => length.is_finite(),
+        Unit::Percentage(Percentage(percentage)) => percentage.is_finite(),
+    }
+}
+
+/// Render the sizing of one dimension, such as `width` with `min-width` and
+/// `max-width`.
+///
+/// Filling the parent grows the block along the flow of the parent, with the
+/// target as the size it grows from, and stretches it across the flow.
+fn sizing(
+    css: &mut Css,
+    dimension: &'static str,
+    min: &'static str,
+    max: &'static str,
+    sizing: &Sizing,
+    main: bool,
+) {
+    let fill = match (sizing.min, sizing.max) {
+        (Flex::Push, _) | (_, Flex::Push) => true,
+        _ => false,
+    };
+
+    let target = sizing.target.as_ref().map(Unit::inline);
+
+    match (fill, main) {
+        (true, true) => {
+            css.property("flex-grow", "1");
+            css.property("flex-basis", target.unwrap_or_else(|| String::from("auto")));
+        }
+        // Stretching only applies to blocks without a size of their own.
+        (true, false) => css.property("align-self", "stretch"),
+        (false, _) => if let Some(target) = target {
+            css.property(dimension, target);
+        },
+    }
+
+    // Limits of zero and infinity are left out, as they do not limit.
+    match sizing.min {
+        Flex::Push => css.property(min, "auto"),
+        Flex::Limit(ref limit) if number(limit) != 0.0 => css.property(min, limit.inline()),
+        Flex::Limit(..) => {}
+    }
+
+    if let Flex::Limit(ref limit) = sizing.max {
+        if finite(limit) {
+            css.property(max, limit.inline());
+        }
+    }
+}
+
+/// Render the size of a block inside a parent with the given flow.
+fn size(size: &Size, parent: &Flow) -> String {
+    let mut css = Css::new();
+
+    let row = match *parent {
+        Flow::Row(..) => true,
+        Flow::Column(..) => false,
+    };
+
+    sizing(&mut css, "width", "min-width", "max-width", &size.width, row);
+    sizing(&mut css, "height", "min-height", "max-height", &size.height, !row);
+
+    css.render()
+}
+
+impl Inline for Hook {
+    fn inline(&self) -> String {
+        let mut css = Css::new();
+
+        match *self {
+            Hook::Single(SingleHook::Top(ref offset)) => css.property("top", offset.inline()),
+            Hook::Single(SingleHook::Bottom(ref offset)) => css.property("bottom", offset.inline()),
+            Hook::Single(SingleHook::Left(ref offset)) => css.property("left", offset.inline()),
+            Hook::Single(SingleHook::Right(ref offset)) => css.property("right", offset.inline()),
+            Hook::Double { ref vertical, ref horizontal } => {
+                match *vertical {
+                    VerticalHook::Top(ref offset) => css.property("top", offset.inline()),
+                    VerticalHook::Bottom(ref offset) => css.property("bottom", offset.inline()),
+                }
+
+                match *horizontal {
+                    HorizontalHook::Left(ref offset) => css.property("left", offset.inline()),
+                    HorizontalHook::Right(ref offset) => css.property("right", offset.inline()),
+                }
+            }
+        }
+
+        css.render()
+    }
+}
+
+impl Inline for Position {
+    fn inline(&self) -> String {
+        let mut css = Css::new();
+
+        // Hooks are offset from the nearest positioned ancestor, which is
+        // either an anchor or another hooked block.
+        let hook = match *self {
+            Position::Passthrough => {
+                css.property("position", "static");
+                None
+            }
+            Position::Anchor => {
+                css.property("position", "relative");
+                None
+            }
+            Position::Hook(ref hook) => {
+                css.property("position", "absolute");
+                Some(hook)
+            }
+            Position::Fixed(ref hook) => {
+                css.property("position", "fixed");
+                Some(hook)
+            }
+            Position::Sticky(ref hook) => {
+                css.property("position", "-webkit-sticky");
+                css.property("position", "sticky");
+                Some(hook)
+            }
+        };
+
+        if let Some(hook) = hook {
+            css.rendered.push(hook.inline());
+        }
+
+        css.render()
+    }
+}
+
+/// A unit as a plain number, with percentages as fractions.
+fn number(unit: &Unit) -> f32 {
+    match *unit {
+        Unit::Length(Length(length)) => length,
+        Unit::Percentage(Percentage(percentage)) => percentage / 100.0,
+    }
+}
+
+impl Inline for Render {
+    fn inline(&self) -> String {
+        let mut css = Css::new();
+        let mut transforms = Vec::new();
+
+        let translation = self.translation;
+
+        if [translation.x, translation.y, translation.z].iter().any(|unit| number(unit) != 0.0) {
+            // Depth cannot be relative to the size of the block.
+            let z = match translation.z {
+                Unit::Length(ref length) => length.inline(),
+                Unit::Percentage(..) => String::from("0"),
+            };
+
+            transforms.push(format!("translate3d({},{},{})",
+                translation.x.inline(),
+                translation.y.inline(),
+                z
+            ));
+        }
+
+        let rotation = self.rotation;
+
+        if rotation.angle.as_degrees() != 0.0 {
+            let (x, y, z) = (
+                number(&rotation.axis[0]),
+                number(&rotation.axis[1]),
+                number(&rotation.axis[2]),
+            );
+
+            if x == 0.0 && y == 0.0 && z == 0.0 {
+                transforms.push(format!("rotate({}deg)", rotation.angle.as_degrees()));
+            } else {
+                let degrees = rotation.angle.as_degrees();
+
+                transforms.push(format!("rotate3d({},{},{},{}deg)", x, y, z, degrees));
+            }
+        }
+
+        if (self.scale.0).0 != 100.0 {
+            transforms.push(format!("scale({})", (self.scale.0).0 / 100.0));
+        }
+
+        // Any transform changes where fixed descendants are positioned from,
+        // so there is none unless it is needed.
+        if !transforms.is_empty() {
+            css.property("transform", transforms.join(" "));
+        }
+
+        css.property("opacity", format!("{}", (self.opacity.0).0 / 100.0));
+
+        // Stacking contexts are only created for blocks that ask for one.
+        let depth = (self.depth.0).0.round() as i32;
+
+        css.property("z-index", if depth != 0 { depth.to_string() } else { String::from("auto") });
+
+        css.render()
+    }
+}
+
+impl Inline for Content {
+    fn inline(&self) -> String {
+        let mut css = Css::new();
+
+        css.property("display", "flex");
+
+        css.property("flex-direction", match self.flow {
+            Flow::Row(Direction::Forwards) => "row",
+            Flow::Row(Direction::Backwards) => "row-reverse",
+            Flow::Column(Direction::Forwards) => "column",
+            Flow::Column(Direction::Backwards) => "column-reverse",
+        });
+
+        css.property("flex-wrap", match self.wrap {
+            Wrap::None => "nowrap",
+            Wrap::Forwards => "wrap",
+            Wrap::Backwards => "wrap-reverse",
+        });
+
+        let (main, cross) = self.align.0.into();
+
+        css.property("justify-content", match main {
+            AlignMode::Start => "flex-start",
+            AlignMode::Center => "center",
+            AlignMode::End => "flex-end",
+            AlignMode::SpaceBetween => "space-between",
+            AlignMode::SpaceAround => "space-around",
+        });
+
+        // Spacing across the flow only applies between wrapped lines, with
+        // children stretched within their line.
+        let (items, lines) = match cross {
+            AlignMode::Start => ("flex-start", "flex-start"),
+            AlignMode::Center => ("center", "center"),
+            AlignMode::End => ("flex-end", "flex-end"),
+            AlignMode::SpaceBetween => ("stretch", "space-between"),
+            AlignMode::SpaceAround => ("stretch", "space-around"),
+        };
+
+        css.property("align-items", items);
+        css.property("align-content", lines);
+
+        // Lengths are relative to the font size, so this scales everything
+        // inside the block.
+        css.property("font-size", self.scale.0.inline());
+
+        css.render()
+    }
+}
+
+impl Inline for Shadow {
+    fn inline(&self) -> String {
+        let (x, y) = self.offset.into();
+
+        let shadow = format!("{} {} {} {} {}",
+            x.inline(),
+            y.inline(),
+            self.blur.inline(),
+            self.spread.inline(),
+            self.color.inline()
+        );
+
+        match self.mode {
+            EdgeMode::Inset => format!("inset {}", shadow),
+            EdgeMode::Outset => shadow,
+        }
+    }
+}
+
+impl Inline for Shadows {
+    fn inline(&self) -> String {
+        let mut css = Css::new();
+
+        if self.0.is_empty() {
+            css.property("box-shadow", "none");
+        } else {
+            let layers: Vec<_> = self.0.iter().map(Shadow::inline).collect();
+
+            css.property("box-shadow", layers.join(","));
+        }
 
         css.render()
     }
@@ -126,16 +565,184 @@ impl Inline for Spacing {
     }
 }
 
+/// Encode bytes as base64, for data URIs.
+fn base64(bytes: &[u8]) -> String {
+    const ALPHABET: &'static [u8] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+
+    let mut encoded = String::with_capacity((bytes.len() + 2) / 3 * 4);
+
+    for chunk in bytes.chunks(3) {
+        let b = [chunk[0], *chunk.get(1).unwrap_or(&0), *chunk.get(2).unwrap_or(&0)];
+        let n = (b[0] as usize) << 16 | (b[1] as usize) << 8 | b[2] as usize;
+
+        for i in 0..4 {
+            if i <= chunk.len() {
+                encoded.push(ALPHABET[n >> (18 - 6 * i) & 63] as char);
+            } else {
+                encoded.push('=');
+            }
+        }
+    }
+
+    encoded
+}
+
+/// The data URI of an image, or `None` if its format is not supported.
+///
+/// Data URIs live in the inline style itself, whereas blob URLs would have to
+/// be revoked once no block shows the image, which is not tracked. Encoding
+/// large images on every render is slow, so recently used images are
+/// remembered, found by their shared bytes and only hashed when those are not
+/// already known.
+fn data_uri(image: &Image) -> Option<String> {
+    lazy_static! {
+        static ref URIS: Mutex<VecDeque<(Arc<Vec<u8>>, u64, String)>> = Mutex::new(VecDeque::new());
+    }
+
+    const CAPACITY: usize = 32;
+
+    let format = image.format()?;
+
+    let mut uris = URIS.lock().unwrap();
+
+    // Holding on to the bytes keeps their address from being reused.
+    let mut key = 0;
+    let found = uris.iter().position(|&(ref bytes, _, _)| Arc::ptr_eq(bytes, &image.bytes)).or_else(|| {
+        let mut hasher = DefaultHasher::new();
+        image.bytes.hash(&mut hasher);
+        key = hasher.finish();
+
+        uris.iter().position(|&(ref bytes, hash, _)| hash == key && bytes == &image.bytes)
+    });
+
+    match found.and_then(|index| uris.remove(index)) {
+        // The most recently used image is evicted last.
+        Some(entry) => {
+            let uri = entry.2.clone();
+            uris.push_back(entry);
+            Some(uri)
+        }
+        None => {
+            let uri = format!("data:{};base64,{}", format.mime(), base64(&image.bytes));
+
+            if uris.len() >= CAPACITY {
+                uris.pop_front();
+            }
+
+            uris.push_back((image.bytes.clone(), key, uri.clone()));
+            Some(uri)
+        }
+    }
+}
+
+impl Inline for Angle {
+    fn inline(&self) -> String {
+        format!("{}deg", self.as_degrees())
+    }
+}
+
+impl Inline for Gradient {
+    fn inline(&self) -> String {
+        let mut arguments = vec![match self.kind {
+            Kind::Linear(ref angle) => angle.inline(),
+            Kind::Radial { shape, center } => {
+                let (x, y) = center.into();
+
+                format!("{} at {} {}",
+                    match shape {
+                        Shape::Circle => "circle",
+                        Shape::Ellipse => "ellipse",
+                    },
+                    x.inline(),
+                    y.inline()
+                )
+            }
+            Kind::Conic { ref from, center } => {
+                let (x, y) = center.into();
+
+                format!("from {} at {} {}", from.inline(), x.inline(), y.inline())
+            }
+        }];
+
+        for stop in &self.stops {
+            arguments.push(format!("{} {}", stop.color.inline(), stop.position.inline()));
+        }
+
+        let function = match self.kind {
+            Kind::Linear(..) => "linear-gradient",
+            Kind::Radial { .. } => "radial-gradient",
+            Kind::Conic { .. } => "conic-gradient",
+        };
+
+        format!("{}({})", function, arguments.join(","))
+    }
+}
+
+/// The image, size, repeat and position of a background layer, or `None` if
+/// the layer cannot be rendered.
+fn layer(layer: &Layer) -> Option<[String; 4]> {
+    match *layer {
+        Layer::Image(ref image) => {
+            let uri = data_uri(image)?;
+
+            let size = match image.size {
+                ImageSize::Scale(scale) => {
+                    let (x, y) = scale.into();
+                    format!("{} {}", x.inline(), y.inline())
+                }
+                ImageSize::Cover => String::from("cover"),
+                ImageSize::Contain => String::from("contain"),
+            };
+
+            let repeat = match image.repeat {
+                Repeat::None => "no-repeat",
+                Repeat::Horizontal => "repeat-x",
+                Repeat::Vertical => "repeat-y",
+                Repeat::Both => "repeat",
+            };
+
+            let (x, y) = image.position.into();
+
+            Some([
+                format!("url(\"{}\")", uri),
+                size,
+                String::from(repeat),
+                format!("{} {}", x.inline(), y.inline()),
+            ])
+        }
+        // A gradient needs two colors to go between, and covers the whole
+        // block.
+        Layer::Gradient(ref gradient) if gradient.stops.len() >= 2 => Some([
+            gradient.inline(),
+            String::from("auto"),
+            String::from("no-repeat"),
+            String::from("0% 0%"),
+        ]),
+        Layer::Gradient(..) => None,
+    }
+}
+
 impl Inline for ui::Background {
     fn inline(&self) -> String {
         let mut css = Css::new();
 
-        match *self {
-            ui::Background::Color(ref color) => {
-                css.property("background-color", color.inline());
-            }
+        css.property("background-color", self.color.inline());
 
-            _ => {}
+        let layers: Vec<_> = self.layers.iter().filter_map(layer).collect();
+
+        if !layers.is_empty() {
+            let properties = [
+                "background-image",
+                "background-size",
+                "background-repeat",
+                "background-position",
+            ];
+
+            for (index, property) in properties.iter().enumerate() {
+                let values: Vec<_> = layers.iter().map(|layer| layer[index].as_str()).collect();
+
+                css.property(property, values.join(","));
+            }
         }
 
         css.render()
@@ -157,38 +764,403 @@ impl Inline for ui::reactive::Reactive {
     }
 }
 
-impl Inline for Style {
-    fn inline(&self) -> String {
-        let mut user_select = Css::new();
+/// The inline style of a block, inside a parent with the given flow.
+///
+/// This is a function rather than an `Inline` implementation for `Style`,
+/// because whether a filling block grows or stretches depends on the flow of
+/// its parent, which the style of the block does not know.
+pub fn block(style: &Style, parent: &Flow) -> String {
+    join(&[
+        style.position.inline(),
+        size(&style.size, parent),
+        style.content.inline(),
+        style.spacing.inline(),
+        style.render.inline(),
+        // After the content, so that a font size replaces its scale.
+        style.font.inline(),
+        text_layout(&style.text, style.font.white_space),
+        style.border.inline(),
+        style.outline.inline(),
+        style.shadow.inline(),
+        style.background.inline(),
+        style.reactive.inline(),
+        user_select(),
+    ])
+}
 
-        user_select.property("-moz-user-select", "none");
-        user_select.property("-webkit-user-select", "none");
-        user_select.property("user-select", "none");
+/// The style of every element, including those that are not blocks.
+///
+/// Layout is left to the browser, so that elements such as `head` stay
+/// hidden.
+fn base() -> String {
+    let style = Style::default();
 
-        [
-            self.spacing.inline(),
-            self.font.inline(),
-            self.border.inline(),
-            self.background.inline(),
-            self.reactive.inline(),
-            user_select.render(),
-        ].join(";")
-    }
+    join(&[
+        style.spacing.inline(),
+        style.font.inline(),
+        style.border.inline(),
+        style.background.inline(),
+        style.reactive.inline(),
+        user_select(),
+    ])
+}
+
+fn user_select() -> String {
+    let mut css = Css::new();
+
+    css.property("-moz-user-select", "none");
+    css.property("-webkit-user-select", "none");
+    css.property("user-select", "none");
+
+    css.render()
 }
 
-fn class<T>(name: &'static str, properties: T) -> String where T: Inline {
+/// Join the rendered parts of a style, skipping those that are empty.
+fn join(parts: &[String]) -> String {
+    let parts: Vec<_> = parts.iter().filter(|part| !part.is_empty()).map(String::as_str).collect();
+
+    parts.join(";")
+}
+
+fn class<T>(name: &str, properties: T) -> String where T: Inline {
     format!("{}{{{}}}", name, properties.inline())
 }
 
 pub fn inject() {
-    let base = class("*", Style::default());
+    let base = format!("*{{{}}}", base());
 
     let mut container = Css::new();
     container.property("height", "100%");
     container.property("display", "flex");
     container.property("flex-direction", "column");
 
-    let container = class("html,body", container);
+    // The root block is laid out in the mount element, as part of a column.
+    let container = class(&format!("html,body,#{}", ffi::mount_id()), container);
 
     ffi::inject_stylesheet(format!("{}{}", base, container));
 }
+
+#[cfg(test)]
+mod test {
+    use ui::border::Side;
+    use super::*;
+
+    /// Whether a rendered style contains a declaration.
+    fn has(css: &str, declaration: &str) -> bool {
+        css.split(';').any(|rendered| rendered == declaration)
+    }
+
+    fn row() -> Flow {
+        Flow::Row(Direction::Forwards)
+    }
+
+    fn column() -> Flow {
+        Flow::Column(Direction::Forwards)
+    }
+
+    #[test]
+    fn base_rule() {
+        let css = base();
+
+        assert!(!css.contains("display"));
+        assert!(!css.contains("flex"));
+        assert!(!css.contains(";;"));
+    }
+
+    #[test]
+    fn content() {
+        let mut style = Style::default();
+        let css = block(&style, &row());
+
+        assert!(has(&css, "display:flex"));
+        assert!(!css.contains(";;"));
+
+        style.content.flow(Flow::Column(Direction::Backwards));
+        style.content.wrap(Wrap::Forwards);
+        style.content.align(AlignMode::SpaceBetween, AlignMode::SpaceAround);
+
+        let css = style.content.inline();
+        assert!(has(&css, "flex-direction:column-reverse"));
+        assert!(has(&css, "flex-wrap:wrap"));
+        assert!(has(&css, "justify-content:space-between"));
+        assert!(has(&css, "align-items:stretch"));
+        assert!(has(&css, "align-content:space-around"));
+    }
+
+    #[test]
+    fn sizing() {
+        assert_eq!("", size(&Size::default(), &row()));
+
+        let mut fill = Size::default();
+        fill.width.target(Unit::percentage(50.0));
+        fill.width.max(Flex::Push);
+        fill.height.max(Flex::Push);
+
+        // Along the flow of the parent, the target is where growing starts.
+        let css = size(&fill, &row());
+        assert!(has(&css, "flex-grow:1"));
+        assert!(has(&css, "flex-basis:50%"));
+        assert!(!css.contains("max-width"));
+        assert!(!css.contains("width:100%"));
+
+        // Across it, the block is stretched.
+        assert!(has(&css, "align-self:stretch"));
+        assert!(!css.contains("height:100%"));
+
+        let css = size(&fill, &column());
+        assert!(has(&css, "flex-basis:auto"));
+        assert!(!css.contains("flex-basis:50%"));
+
+        let mut minimum = Size::default();
+        minimum.width.min(Flex::Push);
+        minimum.width.target(Unit::spx(20f32));
+
+        let css = size(&minimum, &row());
+        assert!(has(&css, "flex-grow:1"));
+        assert!(has(&css, "flex-basis:2em"));
+        assert!(has(&css, "min-width:auto"));
+
+        let mut fixed = Size::default();
+        fixed.width.target(Unit::spx(20f32));
+        fixed.width.max(Flex::Limit(Unit::spx(40f32)));
+
+        let css = size(&fixed, &row());
+        assert!(has(&css, "width:2em"));
+        assert!(has(&css, "max-width:4em"));
+        assert!(!css.contains("flex-grow"));
+    }
+
+    #[test]
+    fn position() {
+        assert!(has(&Position::Passthrough.inline(), "position:static"));
+        assert!(has(&Position::Anchor.inline(), "position:relative"));
+
+        let hook = Hook::Double {
+            vertical: VerticalHook::Bottom(Unit::spx(10f32)),
+            horizontal: HorizontalHook::Left(Unit::percentage(50.0)),
+        };
+
+        let css = Position::Hook(hook).inline();
+        assert!(has(&css, "position:absolute"));
+        assert!(has(&css, "bottom:1em"));
+        assert!(has(&css, "left:50%"));
+
+        let css = Position::Fixed(Hook::Single(SingleHook::Top(Unit::default()))).inline();
+        assert!(has(&css, "position:fixed"));
+        assert!(has(&css, "top:0em"));
+
+        let css = Position::Sticky(Hook::Single(SingleHook::Top(Unit::default()))).inline();
+        assert!(has(&css, "position:-webkit-sticky"));
+        assert!(has(&css, "position:sticky"));
+    }
+
+    #[test]
+    fn render() {
+        let css = Render::default().inline();
+        assert!(!css.contains("transform"));
+        assert!(has(&css, "opacity:1"));
+        assert!(has(&css, "z-index:auto"));
+
+        let mut render = Render::default();
+        render.translate(Unit::spx(10f32), Unit::percentage(50.0));
+        render.rotate(Angle::turns(0.25));
+        render.scale(50);
+        render.depth(3);
+        render.opacity(25);
+
+        let css = render.inline();
+        assert!(has(&css, "transform:translate3d(1em,50%,0em) rotate(90deg) scale(0.5)"));
+        assert!(has(&css, "opacity:0.25"));
+        assert!(has(&css, "z-index:3"));
+
+        render.rotation.axis = [Unit::spx(1f32), Unit::default(), Unit::default()];
+        render.translation = Default::default();
+        render.scale(100);
+
+        assert!(has(&render.inline(), "transform:rotate3d(1,0,0,90deg)"));
+    }
+
+    #[test]
+    fn shadows() {
+        assert!(has(&Shadows::default().inline(), "box-shadow:none"));
+
+        let mut shadows = Shadows::default();
+        shadows.push(Shadow::new((0, 10), 20, Color::rgba(0, 0, 0, 51)).spread(5));
+        shadows.push(Shadow::new((0, 0), 0, Color::rgb(255, 0, 0)).inset());
+
+        assert!(has(&shadows.inline(),
+            "box-shadow:0em 1em 2em 0.5em rgba(0,0,0,0.2),inset 0em 0em 0em 0em rgb(255,0,0)"));
+    }
+
+    #[test]
+    fn borders() {
+        let mut border = Border::default();
+        border.sides((
+            Side::new(1, Color::black(), LineStyle::Solid),
+            Side::new(2, Color::black(), LineStyle::Dashed),
+            Side::new(3, Color::black(), LineStyle::Dotted),
+            Side::new(4, Color::black(), LineStyle::Double),
+        ));
+        border.round(Unit::spx(5f32));
+
+        let css = border.inline();
+        assert!(has(&css, "border-width:0.1em 0.2em 0.3em 0.4em"));
+        assert!(has(&css, "border-style:solid dashed dotted double"));
+        assert!(has(&css, "border-radius:0.5em 0.5em 0.5em 0.5em"));
+    }
+
+    #[test]
+    fn default_border() {
+        let css = Border::default().inline();
+        assert!(has(&css, "border-width:0em 0em 0em 0em"));
+        assert!(has(&css, "border-style:solid solid solid solid"));
+
+        // Giving a side a width is enough to draw it.
+        let mut border = Border::default();
+        border.all(Side { width: Length(2.0), ..Default::default() });
+
+        let css = border.inline();
+        assert!(has(&css, "border-width:0.2em 0.2em 0.2em 0.2em"));
+        assert!(has(&css, "border-style:solid solid solid solid"));
+    }
+
+    #[test]
+    fn outline() {
+        // Focus rings are left alone unless an outline is set.
+        assert_eq!("", Outline::default().inline());
+        assert!(!block(&Style::default(), &row()).contains("outline"));
+
+        let mut outline = Outline::default();
+        outline.set(Side::new(2, Color::rgb(0, 0, 255), LineStyle::Solid));
+        outline.offset(1);
+
+        let css = outline.inline();
+        assert!(has(&css, "outline-style:solid"));
+        assert!(has(&css, "outline-width:0.2em"));
+        assert!(has(&css, "outline-color:rgb(0,0,255)"));
+        assert!(has(&css, "outline-offset:0.1em"));
+    }
+
+    #[test]
+    fn images() {
+        const PNG: &'static [u8] = b"\x89PNG\r\n\x1a\n\0\0\0\rIHDR";
+
+        let image = Image::new(PNG)
+            .size(ImageSize::Contain)
+            .repeat(Repeat::Horizontal)
+            .position(Unit::percentage(50.0), Unit::spx(10f32));
+
+        let mut background = ui::Background::from_color(Color::rgb(0, 0, 0));
+        background.layer(image.clone());
+        background.layer(Image::new(&b"plain text"[..]));
+
+        // Unsupported images are skipped.
+        let css = background.inline();
+        assert!(has(&css, "background-color:rgb(0,0,0)"));
+        assert!(css.contains(&format!("background-image:url(\"data:image/png;base64,{}\");", base64(PNG))));
+        assert!(has(&css, "background-size:contain"));
+        assert!(has(&css, "background-repeat:repeat-x"));
+        assert!(has(&css, "background-position:50% 1em"));
+
+        // Equal bytes give the same URI whether they are shared or not.
+        let uri = data_uri(&image);
+        assert_eq!(uri, data_uri(&image.clone()));
+        assert_eq!(uri, data_uri(&Image::new(PNG)));
+    }
+
+    #[test]
+    fn gradients() {
+        let black = Color::rgb(0, 0, 0);
+        let white = Color::rgb(255, 255, 255);
+
+        let linear = Gradient::linear(Angle::degrees(90.0)).stop(black, 0).stop(white, 100);
+        assert_eq!("linear-gradient(90deg,rgb(0,0,0) 0%,rgb(255,255,255) 100%)", linear.inline());
+
+        let radial = Gradient::radial(Shape::Circle)
+            .center(Unit::percentage(25.0), Unit::percentage(75.0))
+            .stop(black, 0)
+            .stop(white, 50);
+        assert_eq!("radial-gradient(circle at 25% 75%,rgb(0,0,0) 0%,rgb(255,255,255) 50%)", radial.inline());
+
+        let conic = Gradient::conic(Angle::turns(0.5)).stop(black, 0).stop(white, 100);
+        assert!(conic.inline().starts_with("conic-gradient(from 180deg at 50% 50%,"));
+
+        // A gradient with a single stop is skipped, leaving the other layers.
+        let mut background = ui::Background::default();
+        background.layer(Gradient::linear(Angle::degrees(0.0)).stop(black, 0));
+        background.layer(linear);
+
+        let css = background.inline();
+        assert!(has(&css, "background-image:linear-gradient(90deg,rgb(0,0,0) 0%,rgb(255,255,255) 100%)"));
+        assert!(has(&css, "background-size:auto"));
+        assert!(has(&css, "background-repeat:no-repeat"));
+    }
+
+    #[test]
+    fn font() {
+        let css = font::Font::default().inline();
+        assert!(has(&css, "line-height:normal"));
+        assert!(has(&css, "text-decoration:none"));
+        assert!(has(&css, "white-space:normal"));
+        assert!(!css.contains("font-size"));
+
+        let mut font = font::Font::default();
+        font.size(Unit::spx(16f32));
+        font.line_height(Unit::percentage(150.0));
+        font.letter_spacing(1);
+        font.word_spacing(2);
+        font.align(font::Align::Justify);
+        font.underline();
+        font.overline();
+        font.strike();
+        font.transform(font::Transform::Uppercase);
+        font.white_space(font::WhiteSpace::PreserveLines);
+
+        let css = font.inline();
+        assert!(has(&css, "font-size:1.6em"));
+        assert!(has(&css, "line-height:150%"));
+        assert!(has(&css, "letter-spacing:0.1em"));
+        assert!(has(&css, "word-spacing:0.2em"));
+        assert!(has(&css, "text-align:justify"));
+        assert!(has(&css, "text-decoration:underline overline line-through"));
+        assert!(has(&css, "text-transform:uppercase"));
+        assert!(has(&css, "white-space:pre-line"));
+    }
+
+    #[test]
+    fn text() {
+        assert_eq!("", text_layout(&Text::default(), font::WhiteSpace::Collapse));
+        assert_eq!("", text_layout(&Text::default(), font::WhiteSpace::Preserve));
+
+        let mut label = Text::default();
+        label.truncate();
+
+        let css = text_layout(&label, font::WhiteSpace::Collapse);
+        assert!(has(&css, "white-space:nowrap"));
+        assert!(has(&css, "overflow:hidden"));
+        assert!(has(&css, "text-overflow:ellipsis"));
+        assert!(has(&css, "display:block"));
+
+        assert!(has(&text_layout(&label, font::WhiteSpace::Preserve), "white-space:pre"));
+
+        let css = text_layout(&label, font::WhiteSpace::PreserveLines);
+        assert!(css.find("white-space:nowrap") < css.find("white-space-collapse:preserve-breaks"));
+
+        let mut description = Text::default();
+        description.wrap(text::Wrap::Anywhere);
+        description.clamp(2);
+
+        let css = text_layout(&description, font::WhiteSpace::Collapse);
+        assert!(!css.contains("white-space"));
+        assert!(has(&css, "overflow-wrap:break-word"));
+        assert!(has(&css, "overflow:hidden"));
+        assert!(has(&css, "display:-webkit-box"));
+        assert!(has(&css, "-webkit-box-orient:vertical"));
+        assert!(has(&css, "-webkit-line-clamp:2"));
+
+        // The text layout comes after the content, so it replaces its display.
+        let style = Style::new(|style| style.text.clamp(2));
+        let css = block(&style, &row());
+        assert!(css.find("display:flex") < css.find("display:-webkit-box"));
+    }
+}
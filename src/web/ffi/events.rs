@@ -5,7 +5,8 @@ use std::sync::Mutex;
 use super::{AtomId, EventType};
 
 type Callback = Box<Fn(String) + Send>;
-type EventMap = HashMap<EventType, Callback>;
+/// The callbacks of an atom. A callback is `None` while it is running.
+type EventMap = HashMap<EventType, Option<Callback>>;
 
 lazy_static! {
     static ref EVENTS: Mutex<RefCell<HashMap<AtomId, EventMap>>> = {
@@ -14,12 +15,29 @@ lazy_static! {
 }
 
 pub fn call(type_: EventType, atom: AtomId, json_data: String) {
-    let guard = EVENTS.lock().unwrap();
-    let map = guard.borrow();
+    // Take the handler out of the map so that the lock is not held while it
+    // runs. Handlers dispatch messages, which re-render and register events.
+    let handler = {
+        let guard = EVENTS.lock().unwrap();
+        let mut map = guard.borrow_mut();
+
+        map.get_mut(&atom)
+            .and_then(|event_map| event_map.get_mut(&type_))
+            .and_then(Option::take)
+    };
+
+    if let Some(handler) = handler {
+        handler(json_data);
 
-    if let Some(event_map) = map.get(&atom) {
-        if let Some(handler) = event_map.get(&type_) {
-            handler(json_data);
+        let guard = EVENTS.lock().unwrap();
+        let mut map = guard.borrow_mut();
+
+        // Put the handler back unless it was deleted or replaced while it was
+        // running.
+        if let Some(slot) = map.get_mut(&atom).and_then(|event_map| event_map.get_mut(&type_)) {
+            if slot.is_none() {
+                *slot = Some(handler);
+            }
         }
     }
 }
@@ -33,13 +51,18 @@ where
     let mut atom_map = guard.borrow_mut();
 
     if let Some(event_map) = atom_map.get_mut(&id) {
-        event_map.insert(type_, callback);
+        event_map.insert(type_, Some(callback));
 
         // Early return to avoid LL error.
         return
     }
 
     let mut event_map = HashMap::new();
-    event_map.insert(type_, callback);
+    event_map.insert(type_, Some(callback));
     atom_map.insert(id, event_map);
 }
+
+/// Drop every callback of an atom.
+pub fn delete_events(id: AtomId) {
+    EVENTS.lock().unwrap().borrow_mut().remove(&id);
+}
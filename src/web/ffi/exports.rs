@@ -3,7 +3,7 @@
 
 use std::mem;
 
-use super::{events, EventType};
+use super::{events, timers, EventType};
 use super::AtomId;
 
 /// Invoke a callback that accepts 0 arguments.
@@ -29,3 +29,9 @@ pub unsafe extern fn blocks_in_callback_event(atom: u32, type_: u32, ptr: *mut u
         events::call(type_, AtomId::wrap(atom), String::from_raw_parts(ptr, len, len));
     }
 }
+
+/// Call back an expired timer.
+#[no_mangle]
+pub extern fn blocks_in_callback_timeout(id: u32) {
+    timers::call(id);
+}
@@ -7,6 +7,7 @@ use serde::de::DeserializeOwned;
 #[doc(hidden)]
 pub mod exports;
 mod events;
+mod timers;
 
 /// An ID representing an atom.
 ///
@@ -58,6 +59,10 @@ extern {
     fn blocks_out_create_event(atom: u32, type_: u32);
     fn blocks_out_delete_event(atom: u32, type_: u32);
     fn blocks_out_inject_stylesheet(ptr: *const c_char, len: usize);
+    fn blocks_out_now() -> f64;
+    fn blocks_out_timeout(id: u32, delay: u32);
+    fn blocks_out_storage_get(ptr: *const c_char, len: usize, length: *mut [u8; 4]) -> *mut u8;
+    fn blocks_out_storage_set(key_ptr: *const c_char, key_len: usize, ptr: *const c_char, len: usize);
 }
 
 pub fn defer(f: extern fn()) {
@@ -66,6 +71,22 @@ pub fn defer(f: extern fn()) {
     }
 }
 
+/// A monotonic timestamp in milliseconds.
+pub fn now() -> f64 {
+    unsafe {
+        blocks_out_now()
+    }
+}
+
+/// Run a callback once after a delay in milliseconds.
+pub fn timeout<F>(delay: u32, callback: F) where F: 'static + Send + FnOnce() {
+    let id = timers::create_timer(callback);
+
+    unsafe {
+        blocks_out_timeout(id, delay);
+    }
+}
+
 pub fn println<T>(string: T) where T: AsRef<str> {
     let len = string.as_ref().len();
 
@@ -97,6 +118,40 @@ pub fn mount_id() -> String {
     }
 }
 
+/// Read a value from local storage.
+pub fn storage_get<K>(key: K) -> Option<String> where K: AsRef<str> {
+    let key = key.as_ref();
+
+    let len = key.len();
+    let ffi_string = CString::new(key).unwrap();
+    let mut length_buf = [0u8; 4];
+
+    unsafe {
+        let ptr = blocks_out_storage_get(ffi_string.as_ptr(), len, &mut length_buf);
+
+        if ptr.is_null() {
+            None
+        } else {
+            Some(read_string(length_buf, ptr))
+        }
+    }
+}
+
+/// Write a value to local storage.
+pub fn storage_set<K, V>(key: K, value: V) where K: AsRef<str>, V: AsRef<str> {
+    let key = key.as_ref();
+    let value = value.as_ref();
+
+    let key_len = key.len();
+    let key_string = CString::new(key).unwrap();
+    let len = value.len();
+    let ffi_string = CString::new(value).unwrap();
+
+    unsafe {
+        blocks_out_storage_set(key_string.as_ptr(), key_len, ffi_string.as_ptr(), len);
+    }
+}
+
 #[derive(Debug, Clone)]
 pub struct Attribute {
     key: String,
@@ -169,7 +224,11 @@ pub fn update_text_node<T>(text: T, AtomId(id): AtomId) where T: AsRef<str> {
     }
 }
 
-pub fn delete_node(AtomId(id): AtomId) {
+pub fn delete_node(atom: AtomId) {
+    events::delete_events(atom);
+
+    let AtomId(id) = atom;
+
     unsafe {
         blocks_out_delete_node(id);
     }
@@ -200,7 +259,11 @@ pub fn text_node_to_element(AtomId(id): AtomId, attributes: Vec<Attribute>) {
     }
 }
 
-pub fn element_to_text_node<T>(AtomId(id): AtomId, text: T) where T: AsRef<str> {
+pub fn element_to_text_node<T>(atom: AtomId, text: T) where T: AsRef<str> {
+    // Text nodes have no events.
+    events::delete_events(atom);
+
+    let AtomId(id) = atom;
     let text = text.as_ref();
 
     let len = text.len();
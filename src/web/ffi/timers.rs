@@ -0,0 +1,37 @@
+use std::collections::HashMap;
+use std::cell::RefCell;
+use std::sync::Mutex;
+
+type Callback = Box<FnOnce() + Send>;
+
+lazy_static! {
+    static ref TIMERS: Mutex<RefCell<(u32, HashMap<u32, Callback>)>> = {
+        Mutex::new(RefCell::new((0, HashMap::new())))
+    };
+}
+
+/// Register a callback and return its timer ID.
+pub fn create_timer<F>(callback: F) -> u32 where F: 'static + Send + FnOnce() {
+    let guard = TIMERS.lock().unwrap();
+    let mut timers = guard.borrow_mut();
+
+    let id = timers.0;
+    timers.0 = id.wrapping_add(1);
+    timers.1.insert(id, Box::new(callback));
+
+    id
+}
+
+pub fn call(id: u32) {
+    // Release the lock before running the callback, it may create timers.
+    let callback = {
+        let guard = TIMERS.lock().unwrap();
+        let mut timers = guard.borrow_mut();
+
+        timers.1.remove(&id)
+    };
+
+    if let Some(callback) = callback {
+        callback();
+    }
+}
@@ -1,14 +1,20 @@
 use std::mem;
 use std::marker::PhantomData;
-use std::sync::{Arc, Mutex};
+use std::sync::{Arc, Mutex, MutexGuard, TryLockError};
 use std::collections::HashSet;
 
 use ::{State, Reactor, Update};
-use ui::Style;
-use block::{proxy, Block, BlockData, Walker, Group, Child, Build, Consolidator};
-use events::{EventHandler, Upgrade};
-
-use self::css::Inline;
+use queue::{Queue, Job};
+use history::{History, Travel};
+use record::Session;
+use persist::Storage;
+use middleware::{Pipeline, Chain};
+use block::{self, Block, BlockData, Child};
+use block::bake::{Act, BakedChild, BakedWalker, Reuse};
+use block::scope::{Scope, Store};
+use events::{Action, Effect, Event, EventHandler};
+
+use ui::content::{Flow, Direction};
 use self::ffi::{Attribute, EventType};
 use self::atom::Atom;
 
@@ -17,11 +23,14 @@ pub mod ffi;
 mod css;
 mod atom;
 
-enum Rendered {
+type Handler<M> = Arc<Mutex<Box<EventHandler<Message = Action<M>>>>>;
+
+enum Rendered<M> {
     Text(String),
     Element {
-        children: Vec<Atom>,
+        children: Vec<Atom<M>>,
         attributes: Vec<Attribute>,
+        event_handler: Handler<M>,
         registered_events: HashSet<EventType>,
     },
 }
@@ -31,170 +40,138 @@ pub enum Candidate<M> {
     Element {
         children: Vec<Candidate<M>>,
         attributes: Vec<Attribute>,
-        event_handler: Box<EventHandler<Message = M>>,
+        event_handler: Box<EventHandler<Message = Action<M>>>,
+    },
+    /// A node of a memoized child, identified by its memo token, its index
+    /// among the nodes of the child, and the flow of its parent, which its
+    /// style depends on.
+    Memo {
+        key: (u64, usize, Flow),
+        nodes: Arc<Mutex<Reused<M>>>,
     },
 }
 
-impl<B> From<B> for Candidate<B::Message>
-where
-    B: Block,
-    B::EventHandler: 'static,
-    B::Message: 'static,
-{
-    fn from(block: B) -> Self {
+/// The nodes of a memoized child, shared by their candidates.
+///
+/// The child is only turned into candidates when one of its nodes replaces a
+/// node that was rendered from a different memo.
+pub struct Reused<M> {
+    child: Reuse<M>,
+    parent: Flow,
+    candidates: Option<Vec<Option<Candidate<M>>>>,
+}
+
+impl<M> Reused<M> where M: 'static + Send {
+    /// Take the candidate of the node at an index.
+    fn take(&mut self, index: usize) -> Candidate<M> {
+        let child = &self.child;
+        let parent = self.parent;
+
+        self.candidates
+            .get_or_insert_with(|| child.bake().to_candidate(parent).into_iter().map(Some).collect())
+            [index]
+            .take()
+            .expect("blocks: a memoized node was taken twice")
+    }
+}
+
+impl<M> Candidate<M> where M: 'static + Send {
+    /// Walk the root block, keeping component state in a store.
+    fn root<B>(block: B, store: &Store, update: Box<Update<Message = M>>) -> Self
+    where
+        B: Block<Message = M>,
+    {
         let BlockData { child, data } = block.extract();
+        let walker = BakedWalker::<M>::new(Scope::new(store), update);
+
+        // The root is mounted into a column.
+        let parent = Flow::Column(Direction::Forwards);
 
         Candidate::Element {
             attributes: vec![
-                Attribute::new("style", data.style.inline())
+                Attribute::new("style", css::block(&data.style, &parent))
             ],
-            event_handler: Box::new(data.event_handler),
-            children: child.walk(BakedWalker::<B::Message>::new()).to_candidate(),
+            event_handler: Box::new(Act::new(data.event_handler)),
+            children: child.walk(walker).to_candidate(data.style.content.flow),
         }
     }
 }
 
-enum BakedChild<M> {
-    Text(String),
-    Empty,
-    Group(Vec<BakedChild<M>>),
-    Element {
-        style: Style,
-        events: Box<EventHandler<Message = M>>,
-        child: Box<BakedChild<M>>,
-    },
-}
-
-impl<M> BakedChild<M> {
-    fn to_candidate(self) -> Vec<Candidate<M>> {
+impl<M> BakedChild<M> where M: 'static + Send {
+    /// The nodes of a child inside a parent with the given flow.
+    fn to_candidate(self, parent: Flow) -> Vec<Candidate<M>> {
         match self {
             BakedChild::Text(t) => vec![Candidate::Text(t)],
             BakedChild::Empty => vec![],
             BakedChild::Group(children) =>
                 children
                     .into_iter()
-                    .flat_map(BakedChild::to_candidate)
+                    .flat_map(|child| child.to_candidate(parent))
                     .collect(),
             BakedChild::Element { child, style, events } => vec![
                 Candidate::Element {
                     attributes: vec![
-                        Attribute::new("style", style.inline()),
+                        Attribute::new("style", css::block(&style, &parent)),
                     ],
-                    event_handler: events,
-                    children: child.to_candidate(),
+                    event_handler: Box::new(events),
+                    children: child.to_candidate(style.content.flow),
                 },
             ],
-        }
-    }
-}
-
-struct BakedWalker<M> {
-    _message: PhantomData<M>,
-}
-
-impl<M> BakedWalker<M> {
-    fn new() -> Self {
-        BakedWalker {
-            _message: PhantomData
-        }
-    }
-}
-
-impl<M> Walker for BakedWalker<M> where M: 'static + Send {
-    type Message = M;
-    type Walked = BakedChild<M>;
-
-    fn group<M_, G>(self, group: G) -> Self::Walked
-    where
-        G: Group<M_>,
-        Self::Message: From<M_>,
-        M_: 'static + Send,
-    {
-        struct ConsolidatorImpl<M> {
-            _message: PhantomData<M>,
-            children: Vec<BakedChild<M>>,
-        }
-
-        impl<'a, M> Consolidator for &'a mut ConsolidatorImpl<M> where M: 'static + Send {
-            type Message = M;
-
-            fn child<M_, C>(&mut self, child: C)
-            where
-                C: Child<M_>,
-                Self::Message: From<M_>,
-                M_: 'static
-            {
-                self.children.push(child.walk(BakedWalker::<M>::new()));
+            BakedChild::Memo { token, nodes, child } => {
+                let reused = Arc::new(Mutex::new(Reused { child, parent, candidates: None }));
+
+                (0..nodes)
+                    .map(|index| Candidate::Memo {
+                        key: (token, index, parent),
+                        nodes: reused.clone(),
+                    })
+                    .collect()
             }
         }
-
-        let mut consolidator = ConsolidatorImpl::<M> {
-            children: Vec::new(),
-            _message: PhantomData,
-        };
-
-        {
-            let consolidator = proxy::Consolidate::new(&mut consolidator);
-
-            group.consolidate(consolidator);
-        }
-
-        BakedChild::Group(consolidator.children)
-    }
-
-    fn block<E, M_, C>(self, data: Build<E>, child: C) -> Self::Walked
-    where
-        E: EventHandler<Message = M_>,
-        C: Child<M_>,
-        Self::Message: From<M_>,
-        E: 'static,
-        M_: 'static + Send,
-    {
-        BakedChild::Element {
-            style: data.style,
-            events: Box::new(Upgrade::new(data.event_handler)),
-            child: Box::new(child.walk(BakedWalker::<M>::new())),
-        }
-    }
-
-    fn text(self, text: &str) -> Self::Walked {
-        BakedChild::Text(text.to_string())
-    }
-
-    fn empty(self) -> Self::Walked {
-        BakedChild::Empty
     }
 }
 
 pub struct Instance<S, F, B>
 where
-    F: Fn(&S) -> B
+    F: Fn(&S) -> B,
+    S: State,
 {
-    root: Atom,
+    root: Atom<S::Message>,
     state: S,
     app: F,
+    store: Store,
 }
 
-impl<S, F, B> Instance<S, F, B> where F: Fn(&S) -> B, S: State {
-    fn wrap(root: Atom, state: S, app: F) -> Self {
-        Self { root, state, app }
+impl<S, F, B> Instance<S, F, B> where F: Fn(&S) -> B, S: State, B: Block<Message = S::Message> {
+    fn wrap(root: Atom<S::Message>, state: S, app: F) -> Self {
+        Self { root, state, app, store: Store::new() }
     }
 
-    fn render(&self) -> B {
-        (self.app)(&self.state)
+    fn render(&self, update: Box<Update<Message = S::Message>>) -> Candidate<S::Message> {
+        let candidate = Candidate::root((self.app)(&self.state), &self.store, update);
+
+        // Drop the state of components that are no longer rendered.
+        self.store.sweep();
+        candidate
     }
 
-    fn root(&mut self) -> &mut Atom {
+    fn root(&mut self) -> &mut Atom<S::Message> {
         &mut self.root
     }
 
     fn reduce(&mut self, message: S::Message) {
         self.state.reduce(message);
     }
+
+    fn event(&mut self, event: Event) {
+        self.state.event(&event);
+    }
 }
 
-pub struct Handle<S, F, B> where F: Fn(&S) -> B {
+pub struct Handle<S, F, B> where F: Fn(&S) -> B, S: State {
     instance: Arc<Mutex<Instance<S, F, B>>>,
+    queue: Arc<Queue<Job<S::Message>>>,
+    chain: Chain<S::Message>,
 }
 
 impl<S, F, B> Handle<S, F, B>
@@ -203,34 +180,79 @@ where
     S: State<Message = B::Message>,
     F: 'static + Send + Fn(&S) -> B,
 {
+    fn wrap(
+        instance: Instance<S, F, B>,
+        queue: Arc<Queue<Job<S::Message>>>,
+        chain: Chain<S::Message>,
+    ) -> Self {
+        Self {
+            instance: Arc::new(Mutex::new(instance)),
+            queue,
+            chain,
+        }
+    }
+
+    /// Lock the app instance.
+    ///
+    /// All access goes through the message queue, so the lock should never be
+    /// contended. Fail loudly rather than deadlock if it ever is.
+    fn lock(&self) -> MutexGuard<Instance<S, F, B>> {
+        match self.instance.try_lock() {
+            Ok(guard) => guard,
+            Err(TryLockError::WouldBlock) => {
+                panic!("blocks: the app instance was accessed re-entrantly while it was \
+                        being reduced or rendered")
+            }
+            Err(TryLockError::Poisoned(_)) => {
+                panic!("blocks: the app instance is unusable because an earlier call to \
+                        `State::reduce` or the view function panicked")
+            }
+        }
+    }
+
     /// Re render the app.
     fn render(&self) {
-        let mut guard = self.instance.lock().unwrap();
-        let candidate = Candidate::from(guard.render());
-        guard.root().upgrade(candidate, Clone::clone(self));
-    }
+        let mut guard = self.lock();
+
+        // Messages from event handlers and embedded apps pass through the
+        // pipeline.
+        let update = self.chain.wrap(Clone::clone(self));
+        let candidate = guard.render(Update::clone(&update));
 
-    fn message(&self, message: S::Message) {
-        let mut instance = self.instance.lock().unwrap();
-        instance.reduce(message);
+        guard.root().upgrade(candidate, update);
     }
-}
 
-impl<S, F, B> Clone for Handle<S, F, B>
-where
-    F: Fn(&S) -> B
-{
-    fn clone(&self) -> Self {
-        Self { instance: self.instance.clone() }
+    /// Reduce and render every queued message in order.
+    fn flush(&self) {
+        self.queue.drain(|job| match job {
+            Job::Event(event) => self.lock().event(event),
+            Job::Message(message) => {
+                self.lock().reduce(message);
+                self.render();
+            }
+            Job::Effect(effect) => {
+                if let Some(message) = effect() {
+                    self.queue.push(Job::Message(message));
+                }
+
+                self.render();
+            }
+            Job::Render => self.render(),
+        });
     }
 }
 
-impl<S, F, B> From<Instance<S, F, B>> for Handle<S, F, B>
+impl<S, F, B> Clone for Handle<S, F, B>
 where
     F: Fn(&S) -> B,
+    S: State,
 {
-    fn from(instance: Instance<S, F, B>) -> Self {
-        Self { instance: Arc::new(Mutex::new(instance)) }
+    fn clone(&self) -> Self {
+        Self {
+            instance: self.instance.clone(),
+            queue: self.queue.clone(),
+            chain: self.chain.clone(),
+        }
     }
 }
 
@@ -244,28 +266,77 @@ where
     type Message = S::Message;
 
     fn reduce(&self, message: Self::Message) {
-        self.message(message);
-        self.render();
+        self.queue.push(Job::Message(message));
+        self.flush();
     }
 
     fn clone(&self) -> Box<Update<Message = Self::Message>> {
         Box::new(Clone::clone(self))
     }
+
+    fn event(&self, event: Event) {
+        self.queue.push(Job::Event(event));
+        self.flush();
+    }
+
+    fn effect(&self, effect: Effect<Self::Message>) {
+        self.queue.push(Job::Effect(effect));
+        self.flush();
+    }
 }
 
-impl<U> Update for Arc<Mutex<Option<U>>> where U: Update {
-    type Message = U::Message;
+/// The update target handed to `State::new`.
+///
+/// The handle does not exist until the state has been created, so messages
+/// sent before then are queued and processed after the first render.
+struct Deferred<S, F, B> where F: Fn(&S) -> B, S: State {
+    queue: Arc<Queue<Job<S::Message>>>,
+    handle: Arc<Mutex<Option<Handle<S, F, B>>>>,
+}
+
+impl<S, F, B> Update for Deferred<S, F, B>
+where
+    Self: 'static,
+    B: Block,
+    S: State<Message = B::Message>,
+    F: Send + Fn(&S) -> B,
+{
+    type Message = S::Message;
 
     fn reduce(&self, message: Self::Message) {
-        if let Ok(mut guard) = self.lock() {
-            if let Some(ref mut u) = *guard {
-                u.reduce(message);
-            }
+        self.queue.push(Job::Message(message));
+
+        // Never hold this lock while dispatching, the handle may call back
+        // into us.
+        let handle = self.handle
+            .lock()
+            .expect("blocks: the app handle was poisoned")
+            .as_ref()
+            .map(Clone::clone);
+
+        if let Some(handle) = handle {
+            handle.flush();
         }
     }
 
     fn clone(&self) -> Box<Update<Message = Self::Message>> {
-        Box::new(Clone::clone(self)) as Box<Update<Message = Self::Message>>
+        Box::new(Deferred {
+            queue: self.queue.clone(),
+            handle: self.handle.clone(),
+        })
+    }
+}
+
+/// Persistent storage backed by the browser's `localStorage`.
+pub struct LocalStorage;
+
+impl Storage for LocalStorage {
+    fn load(&self, key: &str) -> Option<String> {
+        ffi::storage_get(key)
+    }
+
+    fn save(&self, key: &str, value: &str) {
+        ffi::storage_set(key, value);
     }
 }
 
@@ -274,32 +345,40 @@ impl<U> Update for Arc<Mutex<Option<U>>> where U: Update {
 /// This function is deferred until the wasm module has been loaded correctly.
 /// Because of this, the callee must preserve arguments and create a
 /// `ffi::defer` hook in order to allow use of module imports.
-fn internal_launch<F, B, S>(app: F)
+fn internal_launch<F, B, S, I>(app: F, init: I, pipeline: Pipeline<S::Message>)
 where
     B: Block,
     B::Message: 'static,
     B::EventHandler: 'static,
     F: 'static + Send + Fn(&S) -> B,
     S: State<Message = B::Message>,
+    I: FnOnce(Reactor<S::Message>) -> S,
 {
     css::inject();
 
     let root = Atom::mount();
-    let tmp = Arc::new(Mutex::new(None));
-    let state = S::new(Reactor::new(Clone::clone(&tmp)));
-    let instance = Instance::wrap(root, state, app);
+    let queue = Arc::new(Queue::new());
 
-    let handle: Handle<S, F, B> = instance.into();
+    // The first render is queued ahead of anything sent from `State::new`,
+    // and rendering from the queue means that messages sent while rendering
+    // are processed afterwards rather than re-entering the app.
+    queue.push(Job::Render);
 
-    {
-        let guard = tmp.lock();
+    let deferred = Arc::new(Mutex::new(None));
+    let chain = pipeline.share();
 
-        if let Ok(mut guard) = guard {
-            *guard = Some(Clone::clone(&handle));
-        }
-    }
+    let state = init(Reactor::new(chain.wrap(Deferred {
+        queue: queue.clone(),
+        handle: deferred.clone(),
+    })));
+
+    let instance = Instance::wrap(root, state, app);
+    let handle: Handle<S, F, B> = Handle::wrap(instance, queue, chain);
+
+    *deferred.lock().expect("blocks: the app handle was poisoned") = Some(Clone::clone(&handle));
 
-    handle.render();
+    // Render, then process anything sent from `State::new`.
+    handle.flush();
 
     // Ensure that the app cannot be destroyed.
     mem::forget(handle);
@@ -313,21 +392,66 @@ where
     B::EventHandler: 'static,
     F: 'static + Send + Fn(&S) -> B,
     S: State<Message = B::Message>,
+{
+    spawn(app, S::new, Pipeline::new());
+}
+
+/// Launch an app with every message passing through a middleware pipeline.
+pub fn launch_with<F, B, S>(pipeline: Pipeline<S::Message>, app: F)
+where
+    B: Block,
+    B::Message: 'static,
+    B::EventHandler: 'static,
+    F: 'static + Send + Fn(&S) -> B,
+    S: State<Message = B::Message>,
+{
+    spawn(app, S::new, pipeline);
+}
+
+/// Launch an app from a replayed session.
+///
+/// The initial state is rebuilt by feeding the recorded events and messages
+/// into a fresh state. Messages it sends while the session is being applied
+/// are dropped, so that they are not applied twice.
+pub fn replay<F, B, S>(session: Session<S::Message>, app: F)
+where
+    B: Block,
+    B::Message: 'static + Clone,
+    B::EventHandler: 'static,
+    F: 'static + Send + Fn(&S) -> B,
+    S: State<Message = B::Message>,
+{
+    spawn(app, move |reactor| session.replay(reactor), Pipeline::new());
+}
+
+/// Defer launching an app with a custom state initializer.
+fn spawn<F, B, S, I>(app: F, init: I, pipeline: Pipeline<S::Message>)
+where
+    B: Block,
+    B::Message: 'static,
+    B::EventHandler: 'static,
+    F: 'static + Send + Fn(&S) -> B,
+    S: State<Message = B::Message>,
+    I: 'static + FnOnce(Reactor<S::Message>) -> S,
 {
     static mut APP: Option<Box<Box<Fn()>>> = None;
     static mut TRIGGER: Option<Box<Fn()>> = None;
 
     unsafe {
         TRIGGER = Some(Box::new(|| {
-            let app: Option<Box<Box<F>>> = mem::replace(mem::transmute(&mut APP), None);
+            let app: Option<Box<Box<(F, I, Pipeline<S::Message>)>>> =
+                mem::replace(mem::transmute(&mut APP), None);
 
             match app {
-                Some(app) => internal_launch(**app),
+                Some(app) => {
+                    let (app, init, pipeline) = **app;
+                    internal_launch(app, init, pipeline);
+                }
                 _ => unreachable!(),
             }
         }));
 
-        APP = Some(mem::transmute(Box::new(Box::new(app))));
+        APP = Some(mem::transmute(Box::new(Box::new((app, init, pipeline)))));
     }
 
     extern fn trigger() {
@@ -342,6 +466,24 @@ where
     ffi::defer(trigger);
 }
 
+/// Launch an app with time-travel debugging.
+///
+/// Every message is recorded along with a snapshot of the state, and the app
+/// can be rewound by sending `history::Travel` messages. The view function
+/// receives the `History`, which dereferences to the current state, so an
+/// existing view can be reused with `travel(|h: &History<State>| app(h))`.
+pub fn travel<F, B, S>(app: F)
+where
+    B: Block,
+    B::EventHandler: 'static,
+    F: 'static + Send + Fn(&History<S>) -> B,
+    S: State + Clone,
+    S::Message: Clone,
+    Travel<S::Message>: From<B::Message>,
+{
+    launch(move |history: &History<S>| block::upgrade(app(history)))
+}
+
 /// Launch an app with no state. Useful for static views and prototyping.
 pub fn stateless<F, B>(app: F)
 where